@@ -1,36 +1,295 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops;
+use std::str::FromStr;
+
+use crate::lexer::tokenize;
+use crate::lexer::tokenize_with_definitions;
+use crate::lexer::Token;
+use crate::lexer::TokenKind;
+use crate::propositions::proposition_bit;
+use crate::truth_table::convert_permutation;
+use crate::truth_table::encode_conjunction;
+use crate::truth_table::get_bit_permutations;
+use crate::truth_table::get_propositions;
+use crate::truth_table::index_to_permutation;
+use crate::truth_table::permutation_to_index;
+use crate::InvalidProposition;
+use crate::OperatorAliases;
 use crate::PropositionIdentifier;
 use crate::PropositionTable;
+use crate::SyntaxProfile;
+use crate::TruthTable;
+
+// The maximum nesting depth of subexpressions allowed by default when parsing
+const DEFAULT_MAX_DEPTH: usize = 64;
 
 // Represents a logical expression, which is a recursive tree of propositions/subexpressions and operators
-// Also includes a table of all proposition letters used in the expression, and their respective values
-#[derive(Debug)]
+// Also includes a table of all propositions used in the expression, and their respective values
+#[derive(Clone, PartialEq, Debug)]
 pub struct Expression {
     elements: Vec<ExpressionElement>,
     operators: Vec<Operator>,
     propositions: PropositionTable,
+    // The number of levels of subexpressions in the tree, including this one
+    depth: usize,
 }
 
 // Represents a proposition or a subexpression, and whether it is negated or not
-#[derive(Debug)]
+#[derive(Clone, PartialEq, Debug)]
 struct ExpressionElement {
     token: ExpressionElementToken,
     negation: bool,
 }
 
-// Represents either a single source proposition, or another Expression called a subexpression
-#[derive(Debug)]
+// Represents either a single source proposition, a constant true/false value, or another Expression called a subexpression
+#[derive(Clone, PartialEq, Debug)]
 enum ExpressionElementToken {
     Proposition(PropositionIdentifier),
+    Constant(bool),
     Subexpression(Expression),
 }
 
 // Represents a logical operator
-#[derive(PartialEq, Debug)]
-enum Operator {
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum Operator {
     And,
     Or,
+    Nand,
+    Nor,
+    Xor,
+    Xnor,
+    Implies,
+    Iff,
+}
+
+impl Operator {
+    // Returns how tightly the operator binds its operands, where higher values bind tighter
+    fn precedence(&self) -> u8 {
+        match self {
+            Operator::And | Operator::Nand => 4,
+            Operator::Xor | Operator::Xnor => 3,
+            Operator::Or | Operator::Nor => 2,
+            Operator::Implies => 1,
+            Operator::Iff => 0,
+        }
+    }
+
+    // Returns whether chains of the operator group from the right, such as "A -> B -> C" meaning "A -> (B -> C)"
+    // The biconditional is associative, so it only groups from the right to match the usual convention
+    fn is_right_associative(&self) -> bool {
+        matches!(self, Operator::Implies | Operator::Iff)
+    }
+}
+
+// Represents the settings used when parsing an expression
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    // Whether the single-letter propositions must be consecutive letters starting from A
+    // Propositions with longer names such as "rain" are never checked
+    pub validate_propositions: bool,
+    // The maximum nesting depth of subexpressions
+    pub max_depth: usize,
+    // Whether "a" and "A" are different propositions, rather than single letters being folded to uppercase
+    pub case_sensitive: bool,
+    // Whether adjacent propositions, constants and subexpressions are joined by AND, such as "AB + CD"
+    pub implicit_and: bool,
+    // Which spellings of operators and negation are accepted
+    pub syntax: SyntaxProfile,
+    // Extra spellings accepted on top of those of the syntax profile
+    pub aliases: OperatorAliases,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            validate_propositions: true,
+            max_depth: DEFAULT_MAX_DEPTH,
+            case_sensitive: false,
+            implicit_and: false,
+            syntax: SyntaxProfile::Standard,
+            aliases: OperatorAliases::new(),
+        }
+    }
+}
+
+impl ParseOptions {
+    // Returns the token written as the given word, which may be an alias or a keyword of the syntax profile
+    pub(crate) fn keyword(&self, word: &str) -> Option<TokenKind> {
+        self.aliases
+            .keyword(word)
+            .or_else(|| self.syntax.keyword(word))
+    }
+
+    // Returns the longest symbol at the start of the string, which may be an alias or a symbol of the syntax profile,
+    // as its length in bytes and the token it represents
+    // Aliases take priority over symbols of the same length
+    pub(crate) fn symbol_at(&self, expression: &str) -> Option<(usize, TokenKind)> {
+        let symbol = self
+            .syntax
+            .symbol_at(expression)
+            .map(|(symbol, kind)| (symbol.len(), kind));
+
+        symbol
+            .into_iter()
+            .chain(self.aliases.symbol_at(expression))
+            .max_by_key(|(length, _)| *length)
+    }
+}
+
+// Represents a reason that an expression string could not be parsed
+#[derive(PartialEq, Debug)]
+pub enum ParseError {
+    NonConsecutivePropositions,
+    UnmatchedParenthesis(usize),
+    UnclosedParenthesis(usize),
+    InvalidCharacter(char, usize),
+    MismatchedOperatorCount(usize),
+    NestingTooDeep,
+    TooManyPropositions,
+    InvalidDefinition(usize),
+    CyclicDefinition(usize),
+    ExpansionTooLarge,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ParseError::*;
+
+        match self {
+            NonConsecutivePropositions => write!(
+                f,
+                "Expression does not contain purely consecutive proposition identifiers"
+            ),
+            UnmatchedParenthesis(position) => {
+                write!(f, "Unmatched ')' at position {} in expression", position)
+            }
+            UnclosedParenthesis(position) => {
+                write!(f, "Unclosed '(' at position {} in expression", position)
+            }
+            InvalidCharacter(c, position) => write!(
+                f,
+                "Invalid character '{}' at position {} in expression",
+                c, position
+            ),
+            MismatchedOperatorCount(position) => write!(
+                f,
+                "Mismatched proposition/operator count at position {} in expression",
+                position
+            ),
+            NestingTooDeep => write!(f, "Expression exceeds the maximum nesting depth"),
+            TooManyPropositions => write!(
+                f,
+                "Expression contains more than {} propositions",
+                PropositionIdentifier::MAX_COUNT
+            ),
+            InvalidDefinition(position) => write!(
+                f,
+                "Invalid definition at position {}, definitions must be written as 'NAME := expression;'",
+                position
+            ),
+            CyclicDefinition(position) => write!(
+                f,
+                "Definition at position {} uses itself, either directly or through other definitions",
+                position
+            ),
+            ExpansionTooLarge => write!(
+                f,
+                "Expression is too large once its definitions are expanded"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    // Returns the byte position in the expression string where the error was found, if it has one
+    pub fn position(&self) -> Option<usize> {
+        use ParseError::*;
+
+        match self {
+            UnmatchedParenthesis(position)
+            | UnclosedParenthesis(position)
+            | InvalidCharacter(_, position)
+            | MismatchedOperatorCount(position)
+            | InvalidDefinition(position)
+            | CyclicDefinition(position) => Some(*position),
+            NonConsecutivePropositions
+            | NestingTooDeep
+            | TooManyPropositions
+            | ExpansionTooLarge => None,
+        }
+    }
+
+    // Formats the error along with the line of the expression string containing it, with a caret under its position
+    pub fn annotate(&self, expression_string: &str) -> String {
+        let mut output = format!("Error: {}\n", self);
+
+        if let Some(position) = self.position() {
+            let line_start = expression_string[..position]
+                .rfind('\n')
+                .map_or(0, |i| i + 1);
+            let line_end = expression_string[position..]
+                .find('\n')
+                .map_or(expression_string.len(), |i| position + i);
+            let column = expression_string[line_start..position].chars().count();
+
+            output.push_str(&format!(
+                "    {}\n",
+                &expression_string[line_start..line_end]
+            ));
+            output.push_str(&format!("    {}^\n", " ".repeat(column)));
+        }
+
+        output
+    }
+}
+
+// Represents a reason that a set of named proposition values could not be applied to an expression
+#[derive(PartialEq, Debug)]
+pub enum AssignmentError {
+    InvalidAssignment(String),
+    DuplicateProposition(String),
+    MissingProposition(String),
+    UnusedProposition(String),
+}
+
+impl fmt::Display for AssignmentError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use AssignmentError::*;
+
+        match self {
+            InvalidAssignment(assignment) => write!(
+                f,
+                "Invalid assignment '{}', expected a proposition and a value such as 'A=1'",
+                assignment
+            ),
+            DuplicateProposition(name) => {
+                write!(f, "Proposition '{}' is assigned more than once", name)
+            }
+            MissingProposition(name) => write!(f, "Proposition '{}' is not assigned a value", name),
+            UnusedProposition(name) => {
+                write!(f, "Proposition '{}' is not used in the expression", name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for AssignmentError {}
+
+// Represents a tree path which does not lead to a subexpression
+#[derive(PartialEq, Debug)]
+pub struct InvalidSubexpressionPath(Vec<usize>);
+
+impl fmt::Display for InvalidSubexpressionPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "No subexpression exists at path {:?}", self.0)
+    }
 }
 
+impl std::error::Error for InvalidSubexpressionPath {}
+
 impl ExpressionElement {
     fn new(element: ExpressionElementToken, negation: bool) -> Self {
         Self {
@@ -38,285 +297,3000 @@ impl ExpressionElement {
             negation,
         }
     }
-
-    // Converts a char to a proposition ExpressionElement
-    // TODO: Add range checking here? Probably not necessary
-    fn from_proposition(proposition_letter: char, negation: bool) -> Self {
-        Self::new(
-            ExpressionElementToken::Proposition(PropositionIdentifier::from_char(
-                proposition_letter,
-            )),
-            negation,
-        )
-    }
 }
 
 impl Expression {
+    // The deepest that subexpressions can ever be nested, since evaluating or printing deeper trees could overflow the
+    // stack
+    // Larger maximum depths given when parsing are clamped to it
+    pub const MAX_NESTING_DEPTH: usize = 256;
+
     fn new(
         elements: Vec<ExpressionElement>,
         operators: Vec<Operator>,
         propositions: PropositionTable,
+        depth: usize,
     ) -> Self {
         Self {
             elements,
             operators,
             propositions,
+            depth,
         }
     }
 
-    // Recursively parses an Expression from a string
+    // Recursively parses an Expression from a string, panicking if it is invalid
     pub fn parse(expression_string: &str, validate_propositions: bool) -> Expression {
-        let mut elements: Vec<ExpressionElement> = Vec::new();
-        let mut operators: Vec<Operator> = Vec::new();
-        let propositions = PropositionTable::from_expression_str(expression_string);
+        Self::try_parse(expression_string, validate_propositions)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Recursively parses an Expression from a string, returning an error if it is invalid
+    pub fn try_parse(
+        expression_string: &str,
+        validate_propositions: bool,
+    ) -> Result<Expression, ParseError> {
+        Self::try_parse_with_max_depth(expression_string, validate_propositions, DEFAULT_MAX_DEPTH)
+    }
+
+    // Recursively parses an Expression from a string, returning an error if it is invalid
+    // Subexpressions may only be nested up to the given depth, which prevents stack overflows on deeply nested input
+    pub fn try_parse_with_max_depth(
+        expression_string: &str,
+        validate_propositions: bool,
+        max_depth: usize,
+    ) -> Result<Expression, ParseError> {
+        let options = ParseOptions {
+            validate_propositions,
+            max_depth,
+            ..ParseOptions::default()
+        };
+
+        Self::try_parse_with_options(expression_string, &options)
+    }
+
+    // Parses an Expression from a string using the given options, returning an error if it is invalid
+    pub fn try_parse_with_options(
+        expression_string: &str,
+        options: &ParseOptions,
+    ) -> Result<Expression, ParseError> {
+        let (offset, expression_string) = strip_wrapping(expression_string);
+
+        // The lexer finds errors in the order they appear, so the first one is reported
+        let (tokens, definitions, errors) =
+            tokenize_with_definitions(expression_string, offset, options);
+
+        if let Some(error) = errors.into_iter().next() {
+            return Err(error);
+        }
+
+        let max_depth = options.max_depth.min(Self::MAX_NESTING_DEPTH);
+
+        // Each definition is parsed once, after the definitions it uses, and copied wherever it is used
+        // Uses of a definition act like parentheses around it, so its body is one level deeper
+        let mut parsed = Vec::new();
+
+        for definition in &definitions {
+            let expression = Self::parse_tokens(
+                &definition.tokens,
+                definition.end,
+                options,
+                max_depth.saturating_sub(1),
+                &parsed,
+            )?;
+
+            parsed.push(expression);
+        }
+
+        let expression = Self::parse_tokens(
+            &tokens,
+            offset + expression_string.len(),
+            options,
+            max_depth,
+            &parsed,
+        )?;
+
+        expression.check_propositions(options.validate_propositions)?;
+
+        Ok(expression)
+    }
 
-        // Make sure that the expression does not skip propositions such as in (A, B, D) or (C, D)
-        if validate_propositions && !propositions.validate() {
-            panic!("Expression does not contain purely consecutive proposition identifiers");
+    // Makes sure that the expression does not have more propositions than a truth table can contain, and optionally
+    // that it does not skip propositions such as in (A, B, D) or (C, D)
+    fn check_propositions(&self, validate_propositions: bool) -> Result<(), ParseError> {
+        if self.propositions.count() > PropositionIdentifier::MAX_COUNT {
+            return Err(ParseError::TooManyPropositions);
         }
 
-        let mut input_chars = expression_string.char_indices();
+        if validate_propositions && !self.propositions.validate() {
+            return Err(ParseError::NonConsecutivePropositions);
+        }
+
+        Ok(())
+    }
+
+    // Recursively parses an Expression from a sequence of tokens, where subexpressions may be nested up to the maximum depth
+    // The end position is used for errors when the tokens run out, and uses of definitions are replaced by a copy of
+    // their parsed expressions
+    fn parse_tokens(
+        tokens: &[Token],
+        end: usize,
+        options: &ParseOptions,
+        max_depth: usize,
+        definitions: &[Expression],
+    ) -> Result<Expression, ParseError> {
+        let mut elements: Vec<ExpressionElement> = Vec::new();
+        let mut operators: Vec<Operator> = Vec::new();
+
+        let mut remaining = tokens.iter().enumerate();
         let mut is_negated = false;
 
         use ExpressionElementToken::*;
 
-        while let Some((i, c)) = input_chars.next() {
-            // For each char in the expression
-            match c {
-                // If the proposition character is within the allowed values (based on the assignment instructions)
-                'A'..='D' | 'a'..='d' => {
-                    elements.push(ExpressionElement::from_proposition(c, is_negated));
+        while let Some((i, token)) = remaining.next() {
+            let position = token.span.start;
+
+            // For each token in the expression
+            match &token.kind {
+                TokenKind::Proposition(p) => {
+                    let element = ExpressionElement::new(Proposition(p.clone()), is_negated);
+                    push_element(&mut elements, &mut operators, element, options, position)?;
                     is_negated = false;
                 }
+                TokenKind::Constant(value) => {
+                    let element = ExpressionElement::new(Constant(*value), is_negated);
+                    push_element(&mut elements, &mut operators, element, options, position)?;
+                    is_negated = false;
+                }
+                // Queue a negation to add to the next element, where stacked negations cancel out in pairs
+                TokenKind::Negation => is_negated = !is_negated,
+                TokenKind::Operator(operator) => {
+                    push_operator(&elements, &mut operators, operator.clone(), position)?
+                }
                 // If a subexpression is encountered
-                '(' => {
+                TokenKind::OpenParenthesis => {
+                    if max_depth == 0 {
+                        return Err(ParseError::NestingTooDeep);
+                    }
+
                     // Get the current subexpression and recursively parse it
-                    let subexpression = get_subexpression(&expression_string[i..]);
-                    elements.push(ExpressionElement::new(
-                        Subexpression(Self::parse(&subexpression, false)),
-                        is_negated,
-                    ));
+                    let subexpression = get_subexpression(&tokens[i..])
+                        .ok_or(ParseError::UnclosedParenthesis(position))?;
+                    let closing = &tokens[i + subexpression.len() + 1];
+                    let parsed = Self::parse_tokens(
+                        subexpression,
+                        closing.span.start,
+                        options,
+                        max_depth - 1,
+                        definitions,
+                    )?;
+
+                    let element = ExpressionElement::new(Subexpression(parsed), is_negated);
+                    push_element(&mut elements, &mut operators, element, options, position)?;
 
-                    // Skip the subexpression for its parent's parsing
-                    input_chars.nth(subexpression.len());
+                    // Skip the subexpression and its closing parenthesis for its parent's parsing
+                    remaining.nth(subexpression.len());
+
+                    is_negated = false;
+                }
+                TokenKind::Definition(index) => {
+                    if max_depth == 0 {
+                        return Err(ParseError::NestingTooDeep);
+                    }
 
+                    let definition = Subexpression(definitions[*index].clone());
+                    let element = ExpressionElement::new(definition, is_negated);
+                    push_element(&mut elements, &mut operators, element, options, position)?;
                     is_negated = false;
                 }
                 // If a subexpression is not properly skipped
-                ')' => panic!("Unmatched ')' in expression"),
-                // Queue a negation to add to the next ExpressionToken
-                '!' | '/' => is_negated = true,
-                '&' | '*' => operators.push(Operator::And),
-                '|' | '+' => operators.push(Operator::Or),
-                // Ignore whitespace
-                ' ' | '\n' => (),
-                // Panic on unknown characters
-                _ => panic!("Invalid character '{}' in expression", c),
+                TokenKind::CloseParenthesis => {
+                    return Err(ParseError::UnmatchedParenthesis(position))
+                }
             }
         }
 
-        // Ensure the correct number of elements and operators
-        if elements.len() != operators.len() + 1 {
-            panic!("Mismatched proposition/operator count in expression");
+        // Ensure the expression does not end with an operator, or contain nothing at all
+        if elements.len() == operators.len() {
+            return Err(ParseError::MismatchedOperatorCount(end));
         }
 
-        Self::new(elements, operators, propositions)
+        let (elements, operators) = group_by_precedence(elements, operators, max_depth)?;
+
+        Self::try_from_elements(elements, operators)
     }
 
-    // Recursively sets the values of all propositions in the expression and its subexpressions
-    fn set_values(&mut self, permutation: u8) {
-        // Set the proposition values in the current expression
-        self.propositions.set_all(permutation);
+    // Creates an Expression from its elements and operators, panicking if it would be nested too deeply or use too many
+    // propositions
+    fn from_elements(elements: Vec<ExpressionElement>, operators: Vec<Operator>) -> Self {
+        Self::try_from_elements(elements, operators).unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        use ExpressionElementToken::*;
+    // Creates an Expression from its elements and operators, collecting the propositions used by the elements
+    // Every tree is built through here, so this is where the maximum nesting depth and proposition count are enforced
+    fn try_from_elements(
+        elements: Vec<ExpressionElement>,
+        operators: Vec<Operator>,
+    ) -> Result<Self, ParseError> {
+        let mut identifiers = Vec::new();
+        let mut depth = 1;
 
-        // Set the proposition values in all subexpressions recursively
-        for element in &mut self.elements {
-            match &mut element.token {
-                Subexpression(e) => e.set_values(permutation),
-                Proposition(_) => (),
+        for element in &elements {
+            match &element.token {
+                ExpressionElementToken::Proposition(p) => identifiers.push(p.clone()),
+                ExpressionElementToken::Constant(_) => (),
+                ExpressionElementToken::Subexpression(s) => {
+                    identifiers.append(&mut s.propositions.identifiers());
+                    depth = depth.max(s.depth + 1);
+                }
             }
         }
-    }
 
-    // Recursively evaluates the expression based on its current table
-    // The table must be set before calling this function, or it will cause an error
-    fn evaluate(&self) -> bool {
-        // Evaluate the first element
-        let mut result = self.evaluate_element(&self.elements[0]);
+        if depth > Self::MAX_NESTING_DEPTH {
+            return Err(ParseError::NestingTooDeep);
+        }
 
-        // Evaluate the remaining elements and operators
-        for (i, operator) in self.operators.iter().enumerate() {
-            let element = &self.elements[i + 1];
+        let propositions = PropositionTable::from_identifiers(identifiers);
 
-            match operator {
-                Operator::And => result &= self.evaluate_element(element),
-                Operator::Or => result |= self.evaluate_element(element),
-            }
+        if propositions.count() > PropositionIdentifier::MAX_COUNT {
+            return Err(ParseError::TooManyPropositions);
         }
 
-        result
+        Ok(Self::new(elements, operators, propositions, depth))
     }
 
-    // Evaluates an ExpressionElement, which can be a proposition or a subexpression
-    // Subexpressions are evaluated recursively
-    fn evaluate_element(&self, element: &ExpressionElement) -> bool {
-        use ExpressionElementToken::*;
+    // Parses an Expression written in prefix (Polish) notation such as "| & A B ! C", panicking if it is invalid
+    pub fn parse_prefix(expression_string: &str, validate_propositions: bool) -> Expression {
+        Self::try_parse_prefix(expression_string, validate_propositions)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
 
-        let mut result = match &element.token {
-            Proposition(p) => self.propositions.get_value(p).expect(
-                "[INTERNAL ERROR] Expression proposition values were not set before evaluation",
-            ),
-            Subexpression(s) => s.evaluate(),
+    // Parses an Expression written in prefix (Polish) notation, returning an error if it is invalid
+    // Each operator is followed by its two operands, so parentheses are never needed
+    pub fn try_parse_prefix(
+        expression_string: &str,
+        validate_propositions: bool,
+    ) -> Result<Expression, ParseError> {
+        let options = ParseOptions {
+            validate_propositions,
+            ..ParseOptions::default()
         };
 
-        if element.negation {
-            result = !result;
+        Self::try_parse_prefix_with_options(expression_string, &options)
+    }
+
+    // Parses an Expression written in prefix (Polish) notation using the given options, returning an error if it is
+    // invalid
+    // Each operator counts as one level of nesting, since its operands are nested inside it
+    pub fn try_parse_prefix_with_options(
+        expression_string: &str,
+        options: &ParseOptions,
+    ) -> Result<Expression, ParseError> {
+        let (offset, expression_string) = strip_wrapping(expression_string);
+        let end = offset + expression_string.len();
+        let mut tokens = tokenize_notation(expression_string, offset, options)?
+            .into_iter()
+            .map(|token| (token.span.start, token.kind));
+
+        let max_depth = options.max_depth.min(Self::MAX_NESTING_DEPTH);
+        let root = prefix_element(&mut tokens, end, max_depth)?;
+
+        // Every token must be used by the root operator
+        if let Some((position, _)) = tokens.next() {
+            return Err(ParseError::MismatchedOperatorCount(position));
         }
 
-        result
+        Self::from_root(root, options.validate_propositions)
     }
 
-    // Returns the number of propositions in the expression
-    pub fn proposition_count(&self) -> u8 {
-        self.propositions.count()
+    // Parses an Expression written in postfix (reverse Polish) notation such as "A B & C ! |", panicking if it is invalid
+    pub fn parse_postfix(expression_string: &str, validate_propositions: bool) -> Expression {
+        Self::try_parse_postfix(expression_string, validate_propositions)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
-    // Evaluates a single permutation of propositions
-    pub fn evaluate_permutation(&mut self, permutation: u8) -> bool {
-        self.set_values(permutation);
-        self.evaluate()
-    }
-}
+    // Parses an Expression written in postfix (reverse Polish) notation, returning an error if it is invalid
+    // Each operator follows its two operands, which are combined on a stack, so parentheses are never needed
+    pub fn try_parse_postfix(
+        expression_string: &str,
+        validate_propositions: bool,
+    ) -> Result<Expression, ParseError> {
+        let options = ParseOptions {
+            validate_propositions,
+            ..ParseOptions::default()
+        };
 
-// Return the substring between the first pair of parentheses, excluding the parentheses themselves
-fn get_subexpression(expression: &str) -> String {
-    // If the first character is not a '(', panic with an error message
-    if !expression.starts_with('(') {
-        unreachable!("[INTERNAL ERROR] Subexpression must start with '('");
+        Self::try_parse_postfix_with_options(expression_string, &options)
     }
 
-    let mut subexpression = String::new();
-    let mut depth = 1;
+    // Parses an Expression written in postfix (reverse Polish) notation using the given options, returning an error
+    // if it is invalid
+    // Each operator counts as one level of nesting, since its operands are nested inside it
+    pub fn try_parse_postfix_with_options(
+        expression_string: &str,
+        options: &ParseOptions,
+    ) -> Result<Expression, ParseError> {
+        let (offset, expression_string) = strip_wrapping(expression_string);
+        let max_depth = options.max_depth.min(Self::MAX_NESTING_DEPTH);
 
-    for c in expression.chars().skip(1) {
-        // Adjust the nesting depth to determine when to stop
-        match c {
-            '(' => depth += 1,
-            ')' => depth -= 1,
-            _ => (),
+        // Each element on the stack is stored with its nesting depth
+        let mut stack: Vec<(ExpressionElement, usize)> = Vec::new();
+
+        for Token { kind, span } in tokenize_notation(expression_string, offset, options)? {
+            let position = span.start;
+
+            match kind {
+                TokenKind::Proposition(p) => stack.push((
+                    ExpressionElement::new(ExpressionElementToken::Proposition(p), false),
+                    0,
+                )),
+                TokenKind::Constant(value) => stack.push((
+                    ExpressionElement::new(ExpressionElementToken::Constant(value), false),
+                    0,
+                )),
+                TokenKind::Negation => match stack.last_mut() {
+                    Some((element, _)) => element.negation = !element.negation,
+                    None => return Err(ParseError::MismatchedOperatorCount(position)),
+                },
+                TokenKind::Operator(operator) => {
+                    let (right, right_depth) = stack
+                        .pop()
+                        .ok_or(ParseError::MismatchedOperatorCount(position))?;
+                    let (left, left_depth) = stack
+                        .pop()
+                        .ok_or(ParseError::MismatchedOperatorCount(position))?;
+
+                    let depth = left_depth.max(right_depth) + 1;
+
+                    if depth > max_depth {
+                        return Err(ParseError::NestingTooDeep);
+                    }
+
+                    stack.push((binary_element(left, operator, right), depth));
+                }
+                TokenKind::OpenParenthesis | TokenKind::CloseParenthesis => {
+                    unreachable!("[INTERNAL ERROR] Parentheses are rejected in postfix notation")
+                }
+                TokenKind::Definition(_) => {
+                    unreachable!("[INTERNAL ERROR] Definitions are only read in infix notation")
+                }
+            }
         }
 
-        // Create the substring from the text inside the outerost parentheses
-        // Stop as soon as the corresponding close parentheses have been found
-        if depth > 0 {
-            subexpression.push(c);
-        } else {
-            break;
+        // Every operand must have been combined into a single root
+        match (stack.pop(), stack.is_empty()) {
+            (Some((root, _)), true) => Self::from_root(root, options.validate_propositions),
+            _ => Err(ParseError::MismatchedOperatorCount(
+                offset + expression_string.len(),
+            )),
         }
     }
 
-    subexpression
-}
+    // Finds every error in an infix expression string in a single pass, ordered by their positions
+    // Invalid characters, invalid names and unbalanced parentheses are all reported, and if there are none, the first
+    // error found while parsing is reported instead
+    pub fn diagnose(expression_string: &str) -> Vec<ParseError> {
+        Self::diagnose_with_options(expression_string, &ParseOptions::default())
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    // Finds every error in an infix expression string parsed with the given options, ordered by their positions
+    pub fn diagnose_with_options(
+        expression_string: &str,
+        options: &ParseOptions,
+    ) -> Vec<ParseError> {
+        let (offset, stripped) = strip_wrapping(expression_string);
+        let (tokens, definitions, mut errors) =
+            tokenize_with_definitions(stripped, offset, options);
 
-    #[test]
-    fn test_parse_nonrecursive() {
-        let expression = Expression::parse("A & B", true);
-        assert_eq!(expression.elements.len(), 2);
-        assert_eq!(expression.operators.len(), 1);
-        assert_eq!(expression.operators[0], Operator::And);
+        // Match each ')' with the most recent unmatched '(' in the same definition or main expression
+        let statements = definitions
+            .iter()
+            .map(|definition| &definition.tokens)
+            .chain([&tokens]);
 
-        let mut proposition_num = 0;
+        for statement in statements {
+            let mut open_positions = Vec::new();
 
-        for proposition in &expression.elements {
-            match &proposition.token {
-                ExpressionElementToken::Proposition(p) => {
-                    match proposition_num {
-                        0 => assert_eq!(p, &PropositionIdentifier::A),
-                        1 => assert_eq!(p, &PropositionIdentifier::B),
-                        _ => assert!(false),
+            for token in statement {
+                match token.kind {
+                    TokenKind::OpenParenthesis => open_positions.push(token.span.start),
+                    TokenKind::CloseParenthesis if open_positions.pop().is_none() => {
+                        errors.push(ParseError::UnmatchedParenthesis(token.span.start));
                     }
-
-                    assert_eq!(proposition.negation, false);
-                }
-                ExpressionElementToken::Subexpression(_) => {
-                    assert!(false);
+                    _ => (),
                 }
             }
 
-            proposition_num += 1;
+            errors.extend(
+                open_positions
+                    .into_iter()
+                    .map(ParseError::UnclosedParenthesis),
+            );
         }
-    }
 
-    #[test]
-    fn test_evaluate_nonrecursive() {
-        let mut expression = Expression::parse("A & B", true);
+        if errors.is_empty() {
+            if let Err(error) = Self::try_parse_with_options(expression_string, options) {
+                errors.push(error);
+            }
+        }
 
-        expression.set_values(0b0000);
-        assert!(!expression.evaluate());
+        errors.sort_by_key(ParseError::position);
 
-        expression.set_values(0b010);
-        assert!(!expression.evaluate());
+        errors
+    }
 
-        expression.set_values(0b1000);
-        assert!(!expression.evaluate());
+    // Creates an Expression from the single element at the root of a parsed tree
+    fn from_root(root: ExpressionElement, validate_propositions: bool) -> Result<Self, ParseError> {
+        let expression = Self::try_from_element(root)?;
 
-        expression.set_values(0b1100);
-        assert!(expression.evaluate());
+        expression.check_propositions(validate_propositions)?;
 
-        expression = Expression::parse("!A & !B", true);
+        Ok(expression)
+    }
 
-        expression.set_values(0b0000);
-        assert!(expression.evaluate());
+    // Rebuilds the proposition tables and depths of the expression and all of its subexpressions by rescanning the
+    // tree
+    // This must be called after any change to the tree, since the tables would otherwise be stale
+    pub fn recompute_propositions(&mut self) {
+        let mut identifiers = Vec::new();
+        let mut depth = 1;
 
-        expression.set_values(0b0100);
-        assert!(!expression.evaluate());
+        for element in &mut self.elements {
+            match &mut element.token {
+                ExpressionElementToken::Proposition(p) => identifiers.push(p.clone()),
+                ExpressionElementToken::Constant(_) => (),
+                ExpressionElementToken::Subexpression(s) => {
+                    s.recompute_propositions();
+                    identifiers.append(&mut s.propositions.identifiers());
+                    depth = depth.max(s.depth + 1);
+                }
+            }
+        }
 
-        expression.set_values(0b1000);
-        assert!(!expression.evaluate());
+        self.propositions = PropositionTable::from_identifiers(identifiers);
+        self.depth = depth;
+    }
 
-        expression.set_values(0b1100);
-        assert!(!expression.evaluate());
+    // Replaces every occurrence of a proposition with a copy of the given expression, keeping any negations
+    // Panics if the result would be nested too deeply or use too many propositions
+    pub fn substitute(&mut self, proposition: PropositionIdentifier, replacement: &Expression) {
+        self.try_substitute(proposition, replacement)
+            .unwrap_or_else(|error| panic!("{}", error))
     }
 
-    #[test]
-    fn test_evaluate_recursive() {
-        let mut expression = Expression::parse("(A & B) | (C & D)", true);
+    // Replaces every occurrence of a proposition with a copy of the given expression, keeping any negations
+    // Returns an error without changing the expression if the result would be nested too deeply or use too many
+    // propositions
+    pub fn try_substitute(
+        &mut self,
+        proposition: PropositionIdentifier,
+        replacement: &Expression,
+    ) -> Result<(), ParseError> {
+        if let Some(level) = self.proposition_level(&proposition) {
+            if level + replacement.depth > Self::MAX_NESTING_DEPTH {
+                return Err(ParseError::NestingTooDeep);
+            }
 
-        for i in 0..=15 {
-            expression.set_values(i);
-            assert_eq!(
-                expression.evaluate(),
-                i == 0b1100
-                    || i == 0b1110
-                    || i == 0b1101
-                    || i == 0b0011
-                    || i == 0b1011
-                    || i == 0b0111
-                    || i == 0b1111
+            // The tree is changed in place, so the propositions of the result are counted before replacing anything
+            let propositions = PropositionTable::from_identifiers(
+                self.propositions
+                    .identifiers()
+                    .into_iter()
+                    .filter(|p| *p != proposition)
+                    .chain(replacement.propositions.identifiers()),
             );
+
+            if propositions.count() > PropositionIdentifier::MAX_COUNT {
+                return Err(ParseError::TooManyPropositions);
+            }
         }
-    }
 
-    #[test]
-    fn test_get_subexpression_nested_single() {
-        let expression = "((A | B) & C)";
-        assert_eq!(get_subexpression(expression), "(A | B) & C");
+        self.substitute_elements(&proposition, replacement);
+        self.recompute_propositions();
+
+        Ok(())
     }
 
-    #[test]
-    fn test_get_subexpression_nested_multi() {
-        let expression = "((A | B) & C) & (D & C & A)";
-        assert_eq!(get_subexpression(expression), "(A | B) & C");
+    // Finds the deepest level of the tree that the proposition is used at, where this expression is level 1
+    fn proposition_level(&self, proposition: &PropositionIdentifier) -> Option<usize> {
+        self.elements
+            .iter()
+            .filter_map(|element| match &element.token {
+                ExpressionElementToken::Proposition(p) if p == proposition => Some(1),
+                ExpressionElementToken::Subexpression(s) => {
+                    s.proposition_level(proposition).map(|level| level + 1)
+                }
+                _ => None,
+            })
+            .max()
     }
 
-    #[test]
-    fn test_get_subexpression() {
-        let expression = "(A | B & C)";
-        assert_eq!(get_subexpression(expression), "A | B & C");
+    // Recursively replaces every occurrence of a proposition, without updating the proposition tables
+    fn substitute_elements(
+        &mut self,
+        proposition: &PropositionIdentifier,
+        replacement: &Expression,
+    ) {
+        use ExpressionElementToken::*;
+
+        for element in &mut self.elements {
+            match &mut element.token {
+                Proposition(p) if p == proposition => {
+                    element.token = Subexpression(replacement.clone());
+                }
+                Proposition(_) | Constant(_) => (),
+                Subexpression(s) => s.substitute_elements(proposition, replacement),
+            }
+        }
+    }
+
+    // Parses a validated Expression from a set of string fragments, which are joined with spaces
+    pub fn parse_parts(parts: &[&str]) -> Expression {
+        Self::parse(&parts.join(" "), true)
+    }
+
+    // Creates an Expression from a single element, panicking if it would be nested too deeply
+    fn from_element(element: ExpressionElement) -> Self {
+        Self::try_from_element(element).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Creates an Expression from a single element, unwrapping it if it is a subexpression that is not negated
+    fn try_from_element(element: ExpressionElement) -> Result<Self, ParseError> {
+        match element {
+            ExpressionElement {
+                token: ExpressionElementToken::Subexpression(expression),
+                negation: false,
+            } => Ok(expression),
+            element => Self::try_from_elements(vec![element], Vec::new()),
+        }
+    }
+
+    // Converts the expression into a single element, wrapping it in a subexpression if it has operators
+    fn into_element(mut self) -> ExpressionElement {
+        match self.elements.len() {
+            1 => self.elements.pop().unwrap(),
+            _ => ExpressionElement::new(ExpressionElementToken::Subexpression(self), false),
+        }
+    }
+
+    // Creates an Expression of a single proposition, panicking if the name is invalid
+    // Together with the other builders and the ! operator, this allows expressions to be constructed without parsing
+    // a string, such as Expression::and(Expression::var("A"), !Expression::var("rain")) for "A & !rain"
+    // The builders panic if the result would be nested deeper than MAX_NESTING_DEPTH
+    pub fn var(name: &str) -> Expression {
+        Self::try_var(name).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Creates an Expression of a single proposition, returning an error if the name is not a letter or underscore
+    // followed by letters, digits and underscores
+    pub fn try_var(name: &str) -> Result<Expression, InvalidProposition> {
+        let proposition = name.parse()?;
+
+        Ok(Self::from_element(ExpressionElement::new(
+            ExpressionElementToken::Proposition(proposition),
+            false,
+        )))
+    }
+
+    // Creates an Expression of a single constant true/false value
+    pub fn constant(value: bool) -> Expression {
+        Self::from_element(ExpressionElement::new(
+            ExpressionElementToken::Constant(value),
+            false,
+        ))
+    }
+
+    // Creates the conjunction of two expressions
+    pub fn and(left: Expression, right: Expression) -> Expression {
+        Self::combine(left, Operator::And, right)
+    }
+
+    // Creates the disjunction of two expressions
+    pub fn or(left: Expression, right: Expression) -> Expression {
+        Self::combine(left, Operator::Or, right)
+    }
+
+    // Joins two expressions with an operator, producing the same tree as parsing them would
+    // Chains of operators with the same precedence are kept flat, since they are evaluated from left to right
+    fn combine(left: Expression, operator: Operator, right: Expression) -> Expression {
+        let extends_chain = left.elements.len() > 1
+            && !operator.is_right_associative()
+            && left
+                .operators
+                .iter()
+                .all(|other| other.precedence() == operator.precedence());
+
+        let (mut elements, mut operators) = if extends_chain {
+            (left.elements, left.operators)
+        } else {
+            (vec![left.into_element()], Vec::new())
+        };
+
+        elements.push(right.into_element());
+        operators.push(operator);
+
+        Self::from_elements(elements, operators)
+    }
+
+    // Recursively sets the values of all propositions in the expression and its subexpressions
+    // The permutation is over the propositions of this expression, so it must be called on the root of the tree
+    fn set_values(&mut self, permutation: u32) {
+        // Set the proposition values in the current expression
+        self.propositions.set_all(permutation);
+        self.copy_values_to_subexpressions();
+    }
+
+    // Recursively sets the proposition values in all subexpressions to the values in this expression's table
+    fn copy_values_to_subexpressions(&mut self) {
+        use ExpressionElementToken::*;
+
+        for element in &mut self.elements {
+            match &mut element.token {
+                Subexpression(e) => {
+                    e.propositions.set_from(&self.propositions);
+                    e.copy_values_to_subexpressions();
+                }
+                Proposition(_) | Constant(_) => (),
+            }
+        }
+    }
+
+    // Recursively evaluates the expression based on its current table
+    // The table must be set before calling this function, or it will cause an error
+    fn evaluate(&self) -> bool {
+        // Evaluate the first element
+        let mut result = self.evaluate_element(&self.elements[0]);
+
+        // Evaluate the remaining elements and operators
+        for (i, operator) in self.operators.iter().enumerate() {
+            let element = &self.elements[i + 1];
+
+            match operator {
+                Operator::And => result &= self.evaluate_element(element),
+                Operator::Or => result |= self.evaluate_element(element),
+                Operator::Nand => result = !(result & self.evaluate_element(element)),
+                Operator::Nor => result = !(result | self.evaluate_element(element)),
+                Operator::Xor => result ^= self.evaluate_element(element),
+                Operator::Xnor => result = result == self.evaluate_element(element),
+                Operator::Implies => result = !result | self.evaluate_element(element),
+                Operator::Iff => result = result == self.evaluate_element(element),
+            }
+        }
+
+        result
+    }
+
+    // Evaluates an ExpressionElement, which can be a proposition or a subexpression
+    // Subexpressions are evaluated recursively
+    fn evaluate_element(&self, element: &ExpressionElement) -> bool {
+        use ExpressionElementToken::*;
+
+        let mut result = match &element.token {
+            Proposition(p) => self.propositions.get_value(p).expect(
+                "[INTERNAL ERROR] Expression proposition values were not set before evaluation",
+            ),
+            Constant(value) => *value,
+            Subexpression(s) => s.evaluate(),
+        };
+
+        if element.negation {
+            result = !result;
+        }
+
+        result
+    }
+
+    // Returns the table of propositions used in the expression
+    pub fn propositions(&self) -> &PropositionTable {
+        &self.propositions
+    }
+
+    // Returns the number of propositions in the expression
+    pub fn proposition_count(&self) -> u8 {
+        self.propositions.count()
+    }
+
+    // Checks whether the expression skips any proposition identifiers, such as using A and C but not B
+    // This is the same condition which fails validation when parsing, but it does not panic
+    pub fn has_gaps(&self) -> bool {
+        !self.propositions.validate()
+    }
+
+    // Returns the total number of proposition occurrences in the expression, including inside subexpressions
+    // Unlike proposition_count, repeated propositions are counted each time they appear
+    pub fn leaf_count(&self) -> usize {
+        self.elements
+            .iter()
+            .map(|element| match &element.token {
+                ExpressionElementToken::Proposition(_) => 1,
+                ExpressionElementToken::Constant(_) => 0,
+                ExpressionElementToken::Subexpression(s) => s.leaf_count(),
+            })
+            .sum()
+    }
+
+    // Evaluates a single permutation of propositions
+    pub fn evaluate_permutation(&mut self, permutation: u32) -> bool {
+        self.set_values(permutation);
+        self.evaluate()
+    }
+
+    // Builds a permutation from named proposition values such as "A=1,B=0,C=1" or "rain=1,wind=0"
+    // Every proposition in the expression must be assigned exactly once, and no others may be assigned
+    pub fn permutation_from_assignments(&self, assignments: &str) -> Result<u32, AssignmentError> {
+        let propositions = self.propositions.identifiers();
+        let mut permutation = 0;
+        let mut assigned = Vec::new();
+
+        for assignment in assignments.split(',') {
+            let invalid = || AssignmentError::InvalidAssignment(assignment.trim().to_string());
+
+            let (name, value) = assignment.split_once('=').ok_or_else(invalid)?;
+
+            // An exact match is preferred, so lowercase letters from case-sensitive expressions can be assigned
+            let name = name.trim();
+            let proposition = match propositions.iter().find(|p| p.name() == name) {
+                Some(proposition) => proposition.clone(),
+                None => name.parse().map_err(|_| invalid())?,
+            };
+
+            let value = match value.trim() {
+                "0" => false,
+                "1" => true,
+                _ => return Err(invalid()),
+            };
+
+            if assigned.contains(&proposition) {
+                return Err(AssignmentError::DuplicateProposition(
+                    proposition.to_string(),
+                ));
+            }
+
+            let position = propositions
+                .iter()
+                .position(|p| *p == proposition)
+                .ok_or_else(|| AssignmentError::UnusedProposition(proposition.to_string()))?;
+
+            if value {
+                permutation |= proposition_bit(position);
+            }
+
+            assigned.push(proposition);
+        }
+
+        if let Some(missing) = propositions
+            .into_iter()
+            .find(|proposition| !assigned.contains(proposition))
+        {
+            return Err(AssignmentError::MissingProposition(missing.to_string()));
+        }
+
+        Ok(permutation)
+    }
+
+    // Evaluates the subexpression at the given tree path for a single permutation of propositions
+    // Each step of the path is the index of a subexpression in the elements of the previous expression
+    // Returns an error if the path does not lead to a subexpression
+    pub fn evaluate_subexpression_permutation(
+        &mut self,
+        path: &[usize],
+        permutation: u32,
+    ) -> Result<bool, InvalidSubexpressionPath> {
+        self.set_values(permutation);
+
+        Ok(self.subexpression_at(path)?.evaluate())
+    }
+
+    // Returns the label of the subexpression at the given tree path, such as "(A & B)", or an error if the path does
+    // not lead to a subexpression
+    pub fn subexpression_label(&self, path: &[usize]) -> Result<String, InvalidSubexpressionPath> {
+        Ok(format!("({})", self.subexpression_at(path)?))
+    }
+
+    // Returns the tree paths of all subexpressions, with each inner subexpression before the one containing it
+    pub fn subexpression_paths(&self) -> Vec<Vec<usize>> {
+        let mut paths = Vec::new();
+
+        for (i, element) in self.elements.iter().enumerate() {
+            if let ExpressionElementToken::Subexpression(s) = &element.token {
+                for mut path in s.subexpression_paths() {
+                    path.insert(0, i);
+                    paths.push(path);
+                }
+
+                paths.push(vec![i]);
+            }
+        }
+
+        paths
+    }
+
+    // Evaluates every subexpression for a single permutation of propositions, returning each label and value
+    // Subexpressions are in the same order as subexpression_paths
+    pub fn labeled_subexpression_values(&mut self, permutation: u32) -> Vec<(String, bool)> {
+        self.subexpression_paths()
+            .iter()
+            .map(|path| {
+                let invalid_path =
+                    "[INTERNAL ERROR] Subexpression paths do not lead to subexpressions";
+
+                (
+                    self.subexpression_label(path).expect(invalid_path),
+                    self.evaluate_subexpression_permutation(path, permutation)
+                        .expect(invalid_path),
+                )
+            })
+            .collect()
+    }
+
+    // Follows a tree path to a subexpression, returning an error if the path does not lead to one
+    fn subexpression_at(&self, path: &[usize]) -> Result<&Expression, InvalidSubexpressionPath> {
+        let mut expression = self;
+
+        for i in path {
+            expression = match expression.elements.get(*i).map(|element| &element.token) {
+                Some(ExpressionElementToken::Subexpression(s)) => s,
+                _ => return Err(InvalidSubexpressionPath(path.to_vec())),
+            };
+        }
+
+        Ok(expression)
+    }
+
+    // Converts the expression back into infix notation with the spellings of the syntax profile
+    // Subexpressions are only parenthesized where precedence, associativity or negation requires it
+    pub fn to_string_with_syntax(&self, syntax: SyntaxProfile) -> String {
+        self.format_minimal(syntax).0
+    }
+
+    // Formats the expression in infix notation, along with the precedence of its loosest operator outside of any
+    // parentheses, or None if it has no such operators
+    // The operators are evaluated from left to right, so everything before an operator is its left operand
+    fn format_minimal(&self, syntax: SyntaxProfile) -> (String, Option<u8>) {
+        let (mut infix, mut loosest) = format_element(&self.elements[0], syntax);
+
+        for (operator, element) in self.operators.iter().zip(&self.elements[1..]) {
+            let precedence = operator.precedence();
+            let right_associative = operator.is_right_associative();
+
+            // The left operand needs parentheses if the operator would otherwise only take part of it
+            if loosest
+                .is_some_and(|left| left < precedence || (left == precedence && right_associative))
+            {
+                infix = format!("({})", infix);
+            }
+
+            let (right, right_loosest) = format_element(element, syntax);
+
+            // Operators the syntax cannot print are rewritten as a parenthesized group of ones it can
+            if !syntax.prints_operator(operator) {
+                infix =
+                    format_rewritten(operator, (infix, loosest), (right, right_loosest), syntax);
+                loosest = None;
+
+                continue;
+            }
+
+            // The right operand needs parentheses if it would otherwise take the operator as part of itself
+            let right = match right_loosest {
+                Some(right_precedence)
+                    if right_precedence < precedence
+                        || (right_precedence == precedence && !right_associative) =>
+                {
+                    format!("({})", right)
+                }
+                _ => right,
+            };
+
+            infix = format!("{} {} {}", infix, syntax.operator(operator), right);
+            loosest = Some(precedence.min(loosest.unwrap_or(precedence)));
+        }
+
+        (infix, loosest)
+    }
+
+    // Checks whether two expressions agree on every permutation of their propositions
+    // Expressions over different propositions are compared over the propositions of both, so any propositions
+    // missing from one expression must not affect the result of the other
+    pub fn is_equivalent_to(&mut self, other: &mut Expression) -> bool {
+        let (own, others) = (
+            self.propositions.identifiers(),
+            other.propositions.identifiers(),
+        );
+
+        let mut propositions = own.clone();
+        propositions.extend(others.iter().cloned());
+        propositions.sort();
+        propositions.dedup();
+
+        get_bit_permutations(propositions.len() as u8)
+            .into_iter()
+            .all(|permutation| {
+                self.evaluate_permutation(convert_permutation(permutation, &propositions, &own))
+                    == other.evaluate_permutation(convert_permutation(
+                        permutation,
+                        &propositions,
+                        &others,
+                    ))
+            })
+    }
+
+    // Gets all permutations of the propositions in the expression
+    fn permutations(&self) -> Vec<u32> {
+        get_bit_permutations(self.proposition_count())
+    }
+
+    // Checks whether every permutation is true, stopping at the first false permutation
+    pub fn is_tautology_fast(&mut self) -> bool {
+        self.find_permutation(false).0.is_none()
+    }
+
+    // Checks whether every permutation is false, stopping at the first true permutation
+    pub fn is_contradiction_fast(&mut self) -> bool {
+        self.find_permutation(true).0.is_none()
+    }
+
+    // Finds the first permutation which evaluates to the given result, without evaluating any after it
+    // Also returns how many permutations were evaluated
+    fn find_permutation(&mut self, result: bool) -> (Option<u32>, usize) {
+        let mut evaluations = 0;
+
+        for permutation in self.permutations() {
+            evaluations += 1;
+
+            if self.evaluate_permutation(permutation) == result {
+                return (Some(permutation), evaluations);
+            }
+        }
+
+        (None, evaluations)
+    }
+
+    // Packs the results of all permutations into an integer, where bit i holds the result of conventional row index i
+    pub fn as_truth_integer(&mut self) -> u16 {
+        let proposition_count = self.proposition_count();
+
+        if proposition_count > 4 {
+            panic!("Truth integers can only represent between 1 and 4 propositions");
+        }
+
+        let mut value = 0;
+
+        for permutation in self.permutations() {
+            let index = permutation_to_index(permutation, proposition_count);
+
+            if self.evaluate_permutation(permutation) {
+                value |= 1 << index;
+            }
+        }
+
+        value
+    }
+
+    // Builds the disjunction of conjunctions whose function number is the given truth integer
+    // This is the inverse of as_truth_integer, so bit i of the value is the result of conventional row index i
+    pub fn from_truth_integer(value: u16, proposition_count: u8) -> Expression {
+        if !(1..=4).contains(&proposition_count) {
+            panic!("Truth integers can only represent between 1 and 4 propositions");
+        }
+
+        let propositions = get_propositions(proposition_count);
+        let mut conjunctions = Vec::new();
+
+        for index in 0..(1u32 << proposition_count) {
+            if value & (1 << index) != 0 {
+                let permutation = index_to_permutation(index, proposition_count);
+                conjunctions.push(encode_conjunction(permutation, &propositions));
+            }
+        }
+
+        // A function which is never true has no conjunctions, so use a contradiction which still covers every proposition
+        if conjunctions.is_empty() {
+            let all_true = index_to_permutation((1 << proposition_count) - 1, proposition_count);
+            let contradiction = format!(
+                "{} & {}",
+                encode_conjunction(0, &propositions),
+                encode_conjunction(all_true, &propositions)
+            );
+
+            return Self::parse(&contradiction, true);
+        }
+
+        Self::parse(&conjunctions.join(" | "), true)
+    }
+
+    // Returns the propositions which never affect the result, since flipping them never changes any permutation
+    pub fn irrelevant_variables(&mut self) -> Vec<PropositionIdentifier> {
+        let results: BTreeMap<u32, bool> = self
+            .permutations()
+            .into_iter()
+            .map(|permutation| (permutation, self.evaluate_permutation(permutation)))
+            .collect();
+
+        self.propositions
+            .identifiers()
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| {
+                results.iter().all(|(permutation, result)| {
+                    results.get(&(permutation ^ proposition_bit(*position))) == Some(result)
+                })
+            })
+            .map(|(_, proposition)| proposition)
+            .collect()
+    }
+
+    // Builds a smaller equivalent expression without the irrelevant variables, where the remaining variables are
+    // renamed consecutively starting from A
+    // The result is built from the minimized table of the remaining variables, so its structure is not kept
+    pub fn compacted(&mut self) -> Expression {
+        let irrelevant = self.irrelevant_variables();
+        let propositions = self.propositions.identifiers();
+        let relevant: Vec<PropositionIdentifier> = propositions
+            .iter()
+            .filter(|proposition| !irrelevant.contains(proposition))
+            .cloned()
+            .collect();
+
+        let proposition_count = relevant.len() as u8;
+
+        // An expression without any relevant variables is constant, so it compacts to its value
+        if proposition_count == 0 {
+            return Self::constant(self.evaluate_permutation(0));
+        }
+
+        // Evaluate each permutation of the renamed variables, holding the irrelevant variables false
+        let mut values_and_results = BTreeMap::new();
+
+        for compact_permutation in get_bit_permutations(proposition_count) {
+            let permutation = convert_permutation(compact_permutation, &relevant, &propositions);
+            values_and_results.insert(compact_permutation, self.evaluate_permutation(permutation));
+        }
+
+        let table = TruthTable::new(get_propositions(proposition_count), values_and_results);
+
+        Self::parse(&table.to_minimized_dnf(), true)
+    }
+
+    // Finds all pairs of subexpressions which are logically equivalent over the expression's propositions
+    // Subexpressions are numbered in pre-order, and each pair is a candidate for common subexpression elimination
+    pub fn redundant_subexpressions(&mut self) -> Vec<(usize, usize)> {
+        // Get the value of every subexpression for each permutation
+        let rows: Vec<Vec<bool>> = self
+            .permutations()
+            .into_iter()
+            .map(|permutation| {
+                self.set_values(permutation);
+                self.subexpression_values()
+            })
+            .collect();
+
+        let subexpression_count = rows[0].len();
+        let mut pairs = Vec::new();
+
+        // Compare the truth vectors of each pair of subexpressions
+        for i in 0..subexpression_count {
+            for j in (i + 1)..subexpression_count {
+                if rows.iter().all(|row| row[i] == row[j]) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+
+        pairs
+    }
+
+    // Recursively evaluates all subexpressions in pre-order, ignoring their negations
+    // The table must be set before calling this function, or it will cause an error
+    fn subexpression_values(&self) -> Vec<bool> {
+        let mut values = Vec::new();
+
+        for element in &self.elements {
+            if let ExpressionElementToken::Subexpression(s) = &element.token {
+                values.push(s.evaluate());
+                values.append(&mut s.subexpression_values());
+            }
+        }
+
+        values
+    }
+}
+
+// Formats the expression in infix notation, such as "!A & B | (C -> D)", with only the parentheses that precedence
+// requires
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_string_with_syntax(SyntaxProfile::Standard))
+    }
+}
+
+// Parses an infix expression with the default options, like try_parse with validated propositions
+impl FromStr for Expression {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_parse(s, true)
+    }
+}
+
+// Formats a single element in infix notation, along with the precedence of its loosest operator outside of any
+// parentheses, or None if it has no such operators
+// Negated subexpressions are always parenthesized, unless they only contain a single element
+fn format_element(element: &ExpressionElement, syntax: SyntaxProfile) -> (String, Option<u8>) {
+    let negation = if element.negation {
+        syntax.negation()
+    } else {
+        String::new()
+    };
+
+    match &element.token {
+        ExpressionElementToken::Proposition(p) => (format!("{}{}", negation, p), None),
+        ExpressionElementToken::Constant(value) => (
+            format!("{}{}", negation, if *value { '1' } else { '0' }),
+            None,
+        ),
+        ExpressionElementToken::Subexpression(s) => match s.format_minimal(syntax) {
+            (infix, Some(_)) if element.negation => (format!("{}({})", negation, infix), None),
+            (infix, loosest) => (format!("{}{}", negation, infix), loosest),
+        },
+    }
+}
+
+// Formats an implication as "(!A | B)" or a biconditional as "!(A ^ B)", for syntaxes without spellings for them
+// Each operand is given with the precedence of its loosest operator outside of any parentheses
+fn format_rewritten(
+    operator: &Operator,
+    (left, left_loosest): (String, Option<u8>),
+    (right, right_loosest): (String, Option<u8>),
+    syntax: SyntaxProfile,
+) -> String {
+    let negation = syntax.negation();
+
+    // Operands only need parentheses if they contain an operator that binds as loosely as the one they are used with
+    let group = |operand: String, loosest: Option<u8>, precedence: u8| match loosest {
+        Some(loosest) if loosest <= precedence => format!("({})", operand),
+        _ => operand,
+    };
+
+    match operator {
+        Operator::Implies => format!(
+            "({}{} {} {})",
+            negation,
+            group(left, left_loosest, u8::MAX),
+            syntax.operator(&Operator::Or),
+            group(right, right_loosest, Operator::Or.precedence())
+        ),
+        Operator::Iff => format!(
+            "{}({} {} {})",
+            negation,
+            group(left, left_loosest, Operator::Xor.precedence() - 1),
+            syntax.operator(&Operator::Xor),
+            group(right, right_loosest, Operator::Xor.precedence())
+        ),
+        _ => unreachable!(
+            "[INTERNAL ERROR] Operator {:?} does not need to be rewritten",
+            operator
+        ),
+    }
+}
+
+// Creates the negation of an expression, which cancels out if the expression is already negated
+impl ops::Not for Expression {
+    type Output = Expression;
+
+    fn not(self) -> Self::Output {
+        let mut element = self.into_element();
+        element.negation = !element.negation;
+
+        Self::from_element(element)
+    }
+}
+
+// Removes a leading UTF-8 byte order mark and a pair of surrounding single or double quotes, which are often left
+// by editors and exported files
+// Also returns the byte offset of the remaining expression in the original string
+fn strip_wrapping(expression: &str) -> (usize, &str) {
+    let unmarked = expression.strip_prefix('\u{FEFF}').unwrap_or(expression);
+    let trimmed = unmarked.trim();
+
+    for quote in ['"', '\''] {
+        if let Some(unquoted) = trimmed
+            .strip_prefix(quote)
+            .and_then(|rest| rest.strip_suffix(quote))
+        {
+            let leading = expression.len() - unmarked.trim_start().len();
+            return (leading + quote.len_utf8(), unquoted);
+        }
+    }
+
+    (expression.len() - unmarked.len(), unmarked)
+}
+
+// Splits an expression written in prefix or postfix notation into tokens with the syntax, aliases and case
+// sensitivity of the options
+// Parentheses are never needed in these notations, so they are rejected, and operands are always separated, so words
+// are never split into implicit ANDs
+fn tokenize_notation(
+    expression: &str,
+    offset: usize,
+    options: &ParseOptions,
+) -> Result<Vec<Token>, ParseError> {
+    let options = ParseOptions {
+        implicit_and: false,
+        ..options.clone()
+    };
+    let (tokens, mut errors) = tokenize(expression, offset, &options);
+
+    for token in &tokens {
+        match token.kind {
+            TokenKind::OpenParenthesis => {
+                errors.push(ParseError::InvalidCharacter('(', token.span.start))
+            }
+            TokenKind::CloseParenthesis => {
+                errors.push(ParseError::InvalidCharacter(')', token.span.start))
+            }
+            _ => (),
+        }
+    }
+
+    match errors.into_iter().min_by_key(ParseError::position) {
+        Some(error) => Err(error),
+        None => Ok(tokens),
+    }
+}
+
+// Reads a single operand from a sequence of prefix notation tokens, recursively reading the operands of operators
+// The end position is used for errors when the tokens run out
+fn prefix_element(
+    tokens: &mut impl Iterator<Item = (usize, TokenKind)>,
+    end: usize,
+    max_depth: usize,
+) -> Result<ExpressionElement, ParseError> {
+    // Negations are counted first, so that long chains of them do not recurse
+    let mut negation = false;
+
+    let mut element = loop {
+        match tokens.next().map(|(_, token)| token) {
+            Some(TokenKind::Negation) => negation = !negation,
+            Some(TokenKind::Proposition(p)) => {
+                break ExpressionElement::new(ExpressionElementToken::Proposition(p), false)
+            }
+            Some(TokenKind::Constant(value)) => {
+                break ExpressionElement::new(ExpressionElementToken::Constant(value), false)
+            }
+            Some(TokenKind::Operator(operator)) => {
+                if max_depth == 0 {
+                    return Err(ParseError::NestingTooDeep);
+                }
+
+                let left = prefix_element(tokens, end, max_depth - 1)?;
+                let right = prefix_element(tokens, end, max_depth - 1)?;
+
+                break binary_element(left, operator, right);
+            }
+            Some(TokenKind::OpenParenthesis | TokenKind::CloseParenthesis) => {
+                unreachable!("[INTERNAL ERROR] Parentheses are rejected in prefix notation")
+            }
+            Some(TokenKind::Definition(_)) => {
+                unreachable!("[INTERNAL ERROR] Definitions are only read in infix notation")
+            }
+            None => return Err(ParseError::MismatchedOperatorCount(end)),
+        }
+    };
+
+    element.negation ^= negation;
+
+    Ok(element)
+}
+
+// Creates a subexpression element which applies an operator to two operands
+fn binary_element(
+    left: ExpressionElement,
+    operator: Operator,
+    right: ExpressionElement,
+) -> ExpressionElement {
+    ExpressionElement::new(
+        ExpressionElementToken::Subexpression(Expression::from_elements(
+            vec![left, right],
+            vec![operator],
+        )),
+        false,
+    )
+}
+
+// Adds an element found at the given position to the expression, returning an error if it directly follows
+// another element, unless an implicit AND can be added between them
+fn push_element(
+    elements: &mut Vec<ExpressionElement>,
+    operators: &mut Vec<Operator>,
+    element: ExpressionElement,
+    options: &ParseOptions,
+    position: usize,
+) -> Result<(), ParseError> {
+    if elements.len() > operators.len() {
+        if !options.implicit_and {
+            return Err(ParseError::MismatchedOperatorCount(position));
+        }
+
+        operators.push(Operator::And);
+    }
+
+    elements.push(element);
+
+    Ok(())
+}
+
+// Adds an operator found at the given position to the expression, returning an error if it does not follow an element
+fn push_operator(
+    elements: &[ExpressionElement],
+    operators: &mut Vec<Operator>,
+    operator: Operator,
+    position: usize,
+) -> Result<(), ParseError> {
+    if elements.len() == operators.len() {
+        return Err(ParseError::MismatchedOperatorCount(position));
+    }
+
+    operators.push(operator);
+
+    Ok(())
+}
+
+// Groups the operands of operators which bind tighter than the loosest operator into subexpressions
+// For example, "A | B & C" is grouped as "A | (B & C)", since AND binds tighter than OR
+// Runs of the same operator stay flat rather than becoming a binary tree, since subexpression paths, the verbose
+// columns and the printers all rely on that shape, and a binary tree would nest one level per operator, so long runs
+// such as parsed disjunctions would exceed Expression::MAX_NESTING_DEPTH
+fn group_by_precedence(
+    elements: Vec<ExpressionElement>,
+    operators: Vec<Operator>,
+    max_depth: usize,
+) -> Result<(Vec<ExpressionElement>, Vec<Operator>), ParseError> {
+    let loosest = match operators.iter().map(Operator::precedence).min() {
+        Some(precedence) => precedence,
+        None => return Ok((elements, operators)),
+    };
+
+    // Split the elements into runs, which are separated by the loosest operators
+    let mut elements = elements.into_iter();
+    let mut runs = vec![(vec![elements.next().unwrap()], Vec::new())];
+    let mut loosest_operators = Vec::new();
+
+    for (operator, element) in operators.into_iter().zip(elements) {
+        if operator.precedence() == loosest {
+            loosest_operators.push(operator);
+            runs.push((vec![element], Vec::new()));
+        } else {
+            let (run_elements, run_operators) = runs.last_mut().unwrap();
+            run_elements.push(element);
+            run_operators.push(operator);
+        }
+    }
+
+    // Recursively group each run containing operators into its own subexpression
+    let mut grouped = Vec::new();
+
+    for (mut run_elements, run_operators) in runs {
+        if run_operators.is_empty() {
+            grouped.append(&mut run_elements);
+        } else {
+            let (run_elements, run_operators) =
+                group_by_precedence(run_elements, run_operators, max_depth)?;
+
+            grouped.push(ExpressionElement::new(
+                ExpressionElementToken::Subexpression(Expression::try_from_elements(
+                    run_elements,
+                    run_operators,
+                )?),
+                false,
+            ));
+        }
+    }
+
+    // Nest chains of right-associative operators from the right, since they are otherwise evaluated left to right
+    if loosest_operators.len() > 1 && loosest_operators[0].is_right_associative() {
+        if loosest_operators.len() > max_depth {
+            return Err(ParseError::NestingTooDeep);
+        }
+
+        let mut right = grouped.pop().unwrap();
+
+        while loosest_operators.len() > 1 {
+            let operator = loosest_operators.pop().unwrap();
+            let left = grouped.pop().unwrap();
+
+            right = ExpressionElement::new(
+                ExpressionElementToken::Subexpression(Expression::try_from_elements(
+                    vec![left, right],
+                    vec![operator],
+                )?),
+                false,
+            );
+        }
+
+        grouped.push(right);
+    }
+
+    Ok((grouped, loosest_operators))
+}
+
+// Return the tokens between the first pair of parentheses, excluding the parentheses themselves
+fn get_subexpression(tokens: &[Token]) -> Option<&[Token]> {
+    // If the first token is not a '(', panic with an error message
+    if tokens.first().map(|token| &token.kind) != Some(&TokenKind::OpenParenthesis) {
+        unreachable!("[INTERNAL ERROR] Subexpression must start with '('");
+    }
+
+    let mut depth = 1;
+
+    for (i, token) in tokens.iter().enumerate().skip(1) {
+        // Adjust the nesting depth to determine when to stop
+        match token.kind {
+            TokenKind::OpenParenthesis => depth += 1,
+            TokenKind::CloseParenthesis => depth -= 1,
+            _ => (),
+        }
+
+        // Stop as soon as the corresponding close parenthesis has been found
+        if depth == 0 {
+            return Some(&tokens[1..i]);
+        }
+    }
+
+    // The closing parenthesis was never found
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(
+        clippy::assertions_on_constants,
+        clippy::bool_assert_comparison,
+        clippy::explicit_counter_loop
+    )]
+    fn test_parse_nonrecursive() {
+        let expression = Expression::parse("A & B", true);
+        assert_eq!(expression.elements.len(), 2);
+        assert_eq!(expression.operators.len(), 1);
+        assert_eq!(expression.operators[0], Operator::And);
+
+        let mut proposition_num = 0;
+
+        for proposition in &expression.elements {
+            match &proposition.token {
+                ExpressionElementToken::Proposition(p) => {
+                    match proposition_num {
+                        0 => assert_eq!(p, &PropositionIdentifier::from_char('A')),
+                        1 => assert_eq!(p, &PropositionIdentifier::from_char('B')),
+                        _ => assert!(false),
+                    }
+
+                    assert_eq!(proposition.negation, false);
+                }
+                ExpressionElementToken::Constant(_) | ExpressionElementToken::Subexpression(_) => {
+                    assert!(false);
+                }
+            }
+
+            proposition_num += 1;
+        }
+    }
+
+    #[test]
+    fn test_parse_nonrecursive_negation() {
+        let expression = Expression::parse("!A | 1", true);
+
+        assert_eq!(expression.operators, vec![Operator::Or]);
+        assert_eq!(
+            expression.elements,
+            vec![
+                ExpressionElement::new(
+                    ExpressionElementToken::Proposition(PropositionIdentifier::from_char('A')),
+                    true
+                ),
+                ExpressionElement::new(ExpressionElementToken::Constant(true), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_nonrecursive() {
+        let mut expression = Expression::parse("A & B", true);
+
+        expression.set_values(0b0000);
+        assert!(!expression.evaluate());
+
+        expression.set_values(0b010 << 28);
+        assert!(!expression.evaluate());
+
+        expression.set_values(0b1000 << 28);
+        assert!(!expression.evaluate());
+
+        expression.set_values(0b1100 << 28);
+        assert!(expression.evaluate());
+
+        expression = Expression::parse("!A & !B", true);
+
+        expression.set_values(0b0000);
+        assert!(expression.evaluate());
+
+        expression.set_values(0b0100 << 28);
+        assert!(!expression.evaluate());
+
+        expression.set_values(0b1000 << 28);
+        assert!(!expression.evaluate());
+
+        expression.set_values(0b1100 << 28);
+        assert!(!expression.evaluate());
+    }
+
+    #[test]
+    fn test_evaluate_recursive() {
+        let mut expression = Expression::parse("(A & B) | (C & D)", true);
+
+        for i in 0..=15 {
+            expression.set_values(i << 28);
+            assert_eq!(
+                expression.evaluate(),
+                i == 0b1100
+                    || i == 0b1110
+                    || i == 0b1101
+                    || i == 0b0011
+                    || i == 0b1011
+                    || i == 0b0111
+                    || i == 0b1111
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_parts() {
+        assert_eq!(
+            Expression::parse_parts(&["A", "&", "B"]),
+            Expression::parse("A & B", true)
+        );
+        assert_eq!(
+            Expression::parse_parts(&["!(A", "|", "B)", "&", "C"]),
+            Expression::parse("!(A | B) & C", true)
+        );
+    }
+
+    #[test]
+    fn test_builders() {
+        let (a, b, c) = (
+            Expression::var("A"),
+            Expression::var("b"),
+            Expression::var("C"),
+        );
+
+        // The builders produce the same trees as parsing, so they compare equal
+        assert_eq!(
+            Expression::or(
+                Expression::and(Expression::and(a.clone(), b.clone()), !c.clone()),
+                Expression::constant(false)
+            ),
+            Expression::parse("A & B & !C | 0", true)
+        );
+        assert_eq!(
+            Expression::and(Expression::or(a.clone(), b.clone()), c.clone()),
+            Expression::parse("(A | B) & C", true)
+        );
+        assert_eq!(
+            !Expression::and(a.clone(), !b),
+            Expression::parse("!(A & !B)", true)
+        );
+        assert_eq!(!!a.clone(), a);
+
+        assert_eq!(
+            Expression::try_var("1"),
+            Err("1".parse::<PropositionIdentifier>().unwrap_err())
+        );
+    }
+
+    #[test]
+    fn test_try_parse_errors() {
+        assert!(Expression::try_parse("A & (B | !C)", true).is_ok());
+        assert_eq!(
+            Expression::try_parse("A & C", true),
+            Err(ParseError::NonConsecutivePropositions)
+        );
+        assert_eq!(
+            Expression::try_parse("A & B)", true),
+            Err(ParseError::UnmatchedParenthesis(5))
+        );
+        assert_eq!(
+            Expression::try_parse("A & (B ? C)", true),
+            Err(ParseError::InvalidCharacter('?', 7))
+        );
+        assert_eq!(
+            Expression::try_parse("A & B |", true),
+            Err(ParseError::MismatchedOperatorCount(7))
+        );
+    }
+
+    #[test]
+    fn test_case_sensitive() {
+        let options = ParseOptions {
+            case_sensitive: true,
+            ..ParseOptions::default()
+        };
+
+        let expression = Expression::try_parse_with_options("a & (b | A)", &options).unwrap();
+
+        // Both cases of a letter are different propositions, which each have their own column
+        assert_eq!(expression.proposition_count(), 3);
+        assert_eq!(expression.to_string(), "a & (b | A)");
+        assert_eq!(
+            TruthTable::from_expression(&mut expression.clone()).to_minterm_notation(),
+            "f(A,a,b) = Σm(3,6,7)"
+        );
+        assert_ne!(expression, Expression::parse("A & (B | A)", true));
+
+        // Lowercase letters can be assigned by their exact name
+        assert_eq!(
+            expression.permutation_from_assignments("A=0, a=1, b=1"),
+            Ok(0b011 << 29)
+        );
+
+        // Keywords are not propositions, even if their letters are
+        assert_eq!(
+            Expression::try_parse_with_options("A and a \\land B", &options)
+                .unwrap()
+                .proposition_count(),
+            3
+        );
+        assert!(Expression::try_parse_with_options("a AND b \\land c", &options).is_ok());
+
+        // Each case of letters must be consecutive on its own
+        assert_eq!(
+            Expression::try_parse_with_options("a & c", &options),
+            Err(ParseError::NonConsecutivePropositions)
+        );
+
+        // Case is folded by default
+        assert_eq!(
+            Expression::try_parse("a & (b | A)", true),
+            Expression::try_parse("A & (B | A)", true)
+        );
+    }
+
+    #[test]
+    fn test_comments() {
+        let expected = Expression::parse("A & B | C", true);
+
+        assert_eq!(
+            Expression::parse("# Homework 1\nA & B # both\n\n| C # or neither?\n", true),
+            expected
+        );
+        assert_eq!(
+            Expression::try_parse("A # (\n& ?", true),
+            Err(ParseError::InvalidCharacter('?', 8))
+        );
+
+        // Letters in comments are not propositions
+        let options = ParseOptions {
+            case_sensitive: true,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            Expression::try_parse_with_options("A & B # a or b", &options)
+                .unwrap()
+                .proposition_count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_syntax_profiles() {
+        let expected = Expression::parse("!A & (B | C) -> D", true);
+
+        for (syntax, expression) in [
+            (SyntaxProfile::CStyle, "!A && (B || C) -> D"),
+            (SyntaxProfile::Math, "¬A ∧ (B ∨ C) → D"),
+            (SyntaxProfile::Verilog, "~A & (B | C) -> D"),
+            (SyntaxProfile::Python, "not A and (B or C) implies D"),
+            (SyntaxProfile::Words, "NOT A AND (B OR C) IMPLIES D"),
+            (
+                SyntaxProfile::Latex,
+                "\\neg A \\land (B \\lor C) \\rightarrow D",
+            ),
+        ] {
+            let options = ParseOptions {
+                syntax,
+                ..ParseOptions::default()
+            };
+            let parsed = Expression::try_parse_with_options(expression, &options).unwrap();
+
+            assert_eq!(parsed, expected);
+
+            // Verilog prints the implication in terms of other operators, so it only round-trips to an equivalent
+            // expression
+            let mut reparsed =
+                Expression::try_parse_with_options(&parsed.to_string_with_syntax(syntax), &options)
+                    .unwrap();
+
+            if syntax == SyntaxProfile::Verilog {
+                assert!(reparsed.is_equivalent_to(&mut expected.clone()));
+            } else {
+                assert_eq!(reparsed, expected);
+            }
+        }
+
+        // Verilog has no implication or biconditional operators
+        for (expression, verilog) in [
+            ("A -> B", "(~A | B)"),
+            ("A <-> B", "~(A ^ B)"),
+            ("!A & (B | C) -> D", "(~(~A & (B | C)) | D)"),
+            ("A | B -> C | D", "(~(A | B) | (C | D))"),
+            ("A ^ B <-> C & D", "~(A ^ B ^ C & D)"),
+            ("A <-> B ^ C", "~(A ^ (B ^ C))"),
+            ("(A -> B) & C", "(~A | B) & C"),
+            ("A -> B -> C", "(~A | (~B | C))"),
+        ] {
+            let mut parsed = Expression::parse(expression, true);
+            let printed = parsed.to_string_with_syntax(SyntaxProfile::Verilog);
+            let options = ParseOptions {
+                syntax: SyntaxProfile::Verilog,
+                ..ParseOptions::default()
+            };
+
+            assert_eq!(printed, verilog);
+            assert!(Expression::try_parse_with_options(&printed, &options)
+                .unwrap()
+                .is_equivalent_to(&mut parsed));
+        }
+
+        assert_eq!(
+            expected.to_string_with_syntax(SyntaxProfile::Python),
+            "not A and (B or C) implies D"
+        );
+        assert_eq!(
+            Expression::parse("!(A !& B) ~^ C", true).to_string_with_syntax(SyntaxProfile::Latex),
+            "\\neg (A \\uparrow B) \\odot C"
+        );
+
+        // Each profile only accepts its own spellings
+        let options = ParseOptions {
+            syntax: SyntaxProfile::CStyle,
+            ..ParseOptions::default()
+        };
+        assert_eq!(
+            Expression::try_parse_with_options("A & B", &options),
+            Err(ParseError::InvalidCharacter('&', 2))
+        );
+        assert_eq!(
+            Expression::try_parse_with_options("A AND B", &options),
+            Err(ParseError::MismatchedOperatorCount(2))
+        );
+    }
+
+    #[test]
+    fn test_operator_aliases() {
+        let mut options = ParseOptions::default();
+        options.aliases.add(".", "&").unwrap();
+        options.aliases.add("-", "!").unwrap();
+        options.aliases.add("#", "^").unwrap();
+        options.aliases.add("v", "OR").unwrap();
+
+        assert_eq!(
+            Expression::try_parse_with_options("-A . B # C v D -> E", &options),
+            Ok(Expression::parse("!A & B ^ C | D -> E", true))
+        );
+
+        // Without the alias, '#' still starts a comment
+        options.aliases = OperatorAliases::new();
+        assert_eq!(
+            Expression::try_parse_with_options("A # B", &options),
+            Ok(Expression::parse("A", true))
+        );
+    }
+
+    #[test]
+    fn test_definitions() {
+        assert_eq!(
+            Expression::parse("X := A & B; Y := !C | D; X ^ Y", true),
+            Expression::parse("(A & B) ^ (!C | D)", true)
+        );
+        assert_eq!(
+            Expression::parse(
+                "# Half adder\nsum := A ^ B;\ncarry := A & B; # Both inputs\n!sum & !carry",
+                true
+            ),
+            Expression::parse("!(A ^ B) & !(A & B)", true)
+        );
+
+        // Definitions can be used before they are defined
+        assert_eq!(
+            Expression::parse("Y := X | C; X := A & B; !Y", true),
+            Expression::parse("!((A & B) | C)", true)
+        );
+
+        // Errors in a definition are reported inside it, even if it is never used
+        assert_eq!(
+            Expression::try_parse("X := A &; X | B", true),
+            Err(ParseError::MismatchedOperatorCount(8))
+        );
+        assert_eq!(
+            Expression::try_parse("X := A &; B", true),
+            Err(ParseError::MismatchedOperatorCount(8))
+        );
+        assert_eq!(
+            Expression::try_parse("X := Y & A; Y := !X; X", true),
+            Err(ParseError::CyclicDefinition(0))
+        );
+        assert_eq!(
+            Expression::try_parse("A; B", true),
+            Err(ParseError::InvalidDefinition(0))
+        );
+
+        // Each definition is only parsed once, but copies of definitions which use each other several times still
+        // grow exponentially, so they are limited
+        let doubled = |count: usize| {
+            let mut expression = String::from("X0 := A & B;");
+
+            for i in 1..=count {
+                expression += &format!(" X{} := X{} & X{};", i, i - 1, i - 1);
+            }
+
+            expression + &format!(" X{}", count)
+        };
+
+        let mut expression = Expression::parse(&doubled(8), true);
+        assert!(expression.is_equivalent_to(&mut Expression::parse("A & B", true)));
+        assert_eq!(
+            Expression::try_parse(&doubled(64), true),
+            Err(ParseError::ExpansionTooLarge)
+        );
+    }
+
+    #[test]
+    fn test_implicit_and() {
+        let options = ParseOptions {
+            implicit_and: true,
+            ..ParseOptions::default()
+        };
+
+        for (juxtaposed, explicit) in [
+            ("AB + CD", "(A & B) | (C & D)"),
+            ("A!B + !AB", "(A & !B) | (!A & B)"),
+            ("A(B + C)", "A & (B | C)"),
+            ("(A + B)(C + D)", "(A | B) & (C | D)"),
+            ("ab c", "A & B & C"),
+            ("A1", "A & 1"),
+            ("A AND B C", "A & B & C"),
+        ] {
+            assert_eq!(
+                Expression::try_parse_with_options(juxtaposed, &options),
+                Ok(Expression::parse(explicit, true))
+            );
+        }
+
+        // Words which are not only letters and constants are still names
+        assert_eq!(
+            Expression::try_parse_with_options("is_admin p2", &options),
+            Ok(Expression::parse("is_admin & p2", true))
+        );
+
+        // Juxtaposed letters keep their case in case-sensitive mode
+        let options = ParseOptions {
+            case_sensitive: true,
+            ..options
+        };
+        assert_eq!(
+            Expression::try_parse_with_options("Ab + aB", &options)
+                .unwrap()
+                .proposition_count(),
+            4
+        );
+
+        // Juxtaposition is rejected by default, where juxtaposed letters form a single name
+        assert_eq!(
+            Expression::try_parse("A B + C D", true),
+            Err(ParseError::MismatchedOperatorCount(2))
+        );
+        assert_eq!(
+            Expression::try_parse("AB + CD", true)
+                .unwrap()
+                .propositions()
+                .identifiers(),
+            ["AB", "CD"].map(PropositionIdentifier::from_name)
+        );
+    }
+
+    #[test]
+    fn test_parse_prefix() {
+        for (prefix, infix) in [
+            ("| & A B ! C", "(A & B) | !C"),
+            ("A", "A"),
+            ("!!!A", "!A"),
+            ("! & A B", "!(A & B)"),
+            ("-> A -> B C", "A -> (B -> C)"),
+            ("-> -> A B C", "(A -> B) -> C"),
+            ("<-> A ^ B 1", "A <-> (B ^ 1)"),
+            ("OR AND a b NOT c", "(A & B) | !C"),
+            ("\\lor A \\neg B", "A | !B"),
+            ("!&A B", "A !& B"),
+        ] {
+            assert_eq!(
+                Expression::try_parse_prefix(prefix, true),
+                Ok(Expression::parse(infix, true))
+            );
+        }
+
+        assert_eq!(
+            Expression::try_parse_prefix("& A", true),
+            Err(ParseError::MismatchedOperatorCount(3))
+        );
+        assert_eq!(
+            Expression::try_parse_prefix("& A B C", true),
+            Err(ParseError::MismatchedOperatorCount(6))
+        );
+        assert_eq!(
+            Expression::try_parse_prefix("& A (B)", true),
+            Err(ParseError::InvalidCharacter('(', 4))
+        );
+        assert_eq!(
+            Expression::try_parse_prefix("& A C", true),
+            Err(ParseError::NonConsecutivePropositions)
+        );
+        assert_eq!(
+            Expression::try_parse_prefix(&format!("{}A", "& A ".repeat(100)), true),
+            Err(ParseError::NestingTooDeep)
+        );
+
+        // The maximum depth comes from the options
+        let options = ParseOptions {
+            max_depth: 1,
+            ..ParseOptions::default()
+        };
+        assert!(Expression::try_parse_prefix_with_options("& A B", &options).is_ok());
+        assert_eq!(
+            Expression::try_parse_prefix_with_options("& & A B C", &options),
+            Err(ParseError::NestingTooDeep)
+        );
+
+        let options = ParseOptions {
+            validate_propositions: false,
+            ..ParseOptions::default()
+        };
+        assert!(Expression::try_parse_prefix_with_options("& A C", &options).is_ok());
+
+        // The syntax, aliases and case sensitivity come from the options too
+        let mut options = ParseOptions {
+            syntax: SyntaxProfile::Python,
+            case_sensitive: true,
+            implicit_and: true,
+            ..ParseOptions::default()
+        };
+        options.aliases.add("dot", "&").unwrap();
+
+        let expression =
+            Expression::try_parse_prefix_with_options("or dot a A not AB", &options).unwrap();
+        assert_eq!(expression.proposition_count(), 3);
+        assert_eq!(expression.to_string(), "a & A | !AB");
+        assert_eq!(
+            Expression::try_parse_prefix_with_options("| a A", &options),
+            Err(ParseError::InvalidCharacter('|', 0))
+        );
+        assert_eq!(
+            Expression::try_parse_postfix_with_options("a A dot", &options),
+            Expression::try_parse_prefix_with_options("dot a A", &options)
+        );
+    }
+
+    #[test]
+    fn test_parse_postfix() {
+        for (postfix, infix) in [
+            ("A B & C ! |", "(A & B) | !C"),
+            ("A", "A"),
+            ("A ! ! !", "!A"),
+            ("A B & !", "!(A & B)"),
+            ("A B C -> ->", "A -> (B -> C)"),
+            ("A B -> C ->", "(A -> B) -> C"),
+            ("A B 1 ^ <->", "A <-> (B ^ 1)"),
+            ("a b AND c NOT OR", "(A & B) | !C"),
+            ("A B \\neg \\lor", "A | !B"),
+            ("A B!&", "A !& B"),
+        ] {
+            assert_eq!(
+                Expression::try_parse_postfix(postfix, true),
+                Ok(Expression::parse(infix, true))
+            );
+        }
+
+        // Postfix and prefix notation are mirror images of each other
+        assert_eq!(
+            Expression::parse_postfix("A B & C D | ^", true),
+            Expression::parse_prefix("^ & A B | C D", true)
+        );
+
+        for (invalid, position) in [("A &", 2), ("A B", 3), ("A B & |", 6), ("!", 0), ("&", 0)] {
+            assert_eq!(
+                Expression::try_parse_postfix(invalid, true),
+                Err(ParseError::MismatchedOperatorCount(position))
+            );
+        }
+
+        assert_eq!(
+            Expression::try_parse_postfix("A C &", true),
+            Err(ParseError::NonConsecutivePropositions)
+        );
+        assert_eq!(
+            Expression::try_parse_postfix(&format!("A{}", " A &".repeat(100)), true),
+            Err(ParseError::NestingTooDeep)
+        );
+
+        // The maximum depth comes from the options
+        let options = ParseOptions {
+            max_depth: 1,
+            ..ParseOptions::default()
+        };
+        assert!(Expression::try_parse_postfix_with_options("A B &", &options).is_ok());
+        assert_eq!(
+            Expression::try_parse_postfix_with_options("A B & C &", &options),
+            Err(ParseError::NestingTooDeep)
+        );
+    }
+
+    #[test]
+    fn test_proposition_names() {
+        let mut expression = Expression::parse("rain & !p1 | is_admin", true);
+
+        assert_eq!(
+            expression.propositions().identifiers(),
+            ["is_admin", "p1", "rain"].map(PropositionIdentifier::from_name)
+        );
+        assert_eq!(expression.to_string(), "rain & !p1 | is_admin");
+        assert!(!expression.is_equivalent_to(&mut Expression::parse("(A & !B) | C", true)));
+
+        // Names are only checked for consecutive letters when they are single letters
+        assert_eq!(
+            Expression::try_parse("rain & C", true),
+            Err(ParseError::NonConsecutivePropositions)
+        );
+        assert!(Expression::try_parse("rain & A", true).is_ok());
+
+        // Longer names keep their case, unlike single letters
+        assert_eq!(
+            Expression::parse("Rain | rain", true).proposition_count(),
+            2
+        );
+        assert_eq!(Expression::parse("a | A", true).proposition_count(), 1);
+
+        // A truth table can only contain so many propositions
+        let names: Vec<String> = (0..27).map(|i| format!("p{}", i)).collect();
+        assert_eq!(
+            Expression::try_parse(&names.join(" & "), true),
+            Err(ParseError::TooManyPropositions)
+        );
+    }
+
+    #[test]
+    fn test_error_positions() {
+        for (expression, error) in [
+            ("A & (B C)", ParseError::MismatchedOperatorCount(7)),
+            ("& A", ParseError::MismatchedOperatorCount(0)),
+            ("A | ()", ParseError::MismatchedOperatorCount(5)),
+            ("(A & (B ^ ?))", ParseError::InvalidCharacter('?', 10)),
+            ("A & (B | rain ?)", ParseError::InvalidCharacter('?', 14)),
+            ("\"A & ?\"", ParseError::InvalidCharacter('?', 5)),
+        ] {
+            assert_eq!(Expression::try_parse(expression, true), Err(error));
+        }
+
+        assert_eq!(ParseError::InvalidCharacter('?', 10).position(), Some(10));
+        assert_eq!(ParseError::NonConsecutivePropositions.position(), None);
+    }
+
+    #[test]
+    fn test_diagnose() {
+        assert_eq!(
+            Expression::diagnose("A & B) | ? & (C | rain ~"),
+            vec![
+                ParseError::UnmatchedParenthesis(5),
+                ParseError::InvalidCharacter('?', 9),
+                ParseError::UnclosedParenthesis(13),
+                ParseError::InvalidCharacter('~', 23),
+            ]
+        );
+
+        // Errors which can only be found while parsing are reported alone
+        assert_eq!(
+            Expression::diagnose("A & C"),
+            vec![ParseError::NonConsecutivePropositions]
+        );
+        assert_eq!(
+            Expression::diagnose("(A &) | B"),
+            vec![ParseError::MismatchedOperatorCount(4)]
+        );
+        assert!(Expression::diagnose("(A & B) | !C").is_empty());
+    }
+
+    #[test]
+    fn test_annotate() {
+        assert_eq!(
+            ParseError::InvalidCharacter('?', 9).annotate("A & B | ? & C"),
+            "Error: Invalid character '?' at position 9 in expression\n    A & B | ? & C\n             ^\n"
+        );
+        assert_eq!(
+            ParseError::UnclosedParenthesis(8).annotate("A & B\n| (C"),
+            "Error: Unclosed '(' at position 8 in expression\n    | (C\n      ^\n"
+        );
+        assert_eq!(
+            ParseError::NonConsecutivePropositions.annotate("A & C"),
+            "Error: Expression does not contain purely consecutive proposition identifiers\n"
+        );
+    }
+
+    #[test]
+    fn test_try_parse_nesting_depth() {
+        let nested = format!("{}A{}", "(".repeat(100_000), ")".repeat(100_000));
+        assert_eq!(
+            Expression::try_parse(&nested, true),
+            Err(ParseError::NestingTooDeep)
+        );
+
+        let nested = format!(
+            "{}A{}",
+            "(".repeat(DEFAULT_MAX_DEPTH),
+            ")".repeat(DEFAULT_MAX_DEPTH)
+        );
+        assert!(Expression::try_parse(&nested, true).is_ok());
+
+        assert_eq!(
+            Expression::try_parse_with_max_depth("((A)) & B", true, 1),
+            Err(ParseError::NestingTooDeep)
+        );
+        assert!(Expression::try_parse_with_max_depth("(A) & (B)", true, 1).is_ok());
+
+        // Larger maximum depths are clamped, so they cannot overflow the stack
+        let nested = format!(
+            "{}A{}",
+            "(".repeat(Expression::MAX_NESTING_DEPTH + 1),
+            ")".repeat(Expression::MAX_NESTING_DEPTH + 1)
+        );
+        assert_eq!(
+            Expression::try_parse_with_max_depth(&nested, true, usize::MAX),
+            Err(ParseError::NestingTooDeep)
+        );
+    }
+
+    // Builds an expression nested to the maximum depth, where alternating operators cannot be flattened so each one
+    // adds a level
+    fn deepest_expression() -> Expression {
+        let mut expression = Expression::var("A");
+
+        for i in 0..Expression::MAX_NESTING_DEPTH {
+            expression = if i % 2 == 0 {
+                Expression::or(expression, Expression::var("B"))
+            } else {
+                Expression::and(expression, Expression::var("B"))
+            };
+        }
+
+        expression
+    }
+
+    #[test]
+    fn test_flat_operator_runs() {
+        // Left-associative runs are a single level however long they are, so they never reach the maximum depth
+        let run = vec!["A"; Expression::MAX_NESTING_DEPTH * 4].join(" | ");
+        let expression = Expression::parse(&format!("{} & B", run), true);
+
+        assert_eq!(expression.depth, 2);
+        assert_eq!(expression.elements.len(), Expression::MAX_NESTING_DEPTH * 4);
+    }
+
+    #[test]
+    fn test_built_nesting_depth() {
+        let mut expression = deepest_expression();
+
+        assert_eq!(expression.depth, Expression::MAX_NESTING_DEPTH);
+        assert!(expression.evaluate_permutation(0b11 << 30));
+        assert!(!expression.to_string().is_empty());
+        assert_eq!(expression.clone(), expression);
+
+        // Substituting a proposition checks the depth before changing anything
+        let mut substituted = expression.clone();
+        assert_eq!(
+            substituted.try_substitute(
+                PropositionIdentifier::from_char('A'),
+                &Expression::parse("A | B", true)
+            ),
+            Err(ParseError::NestingTooDeep)
+        );
+        assert_eq!(substituted, expression);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expression exceeds the maximum nesting depth")]
+    fn test_built_nesting_depth_exceeded() {
+        let _ = !deepest_expression();
+    }
+
+    #[test]
+    fn test_redundant_subexpressions() {
+        let mut expression = Expression::parse("(A & B) | (A & B)", true);
+        assert_eq!(expression.redundant_subexpressions(), vec![(0, 1)]);
+
+        let mut expression = Expression::parse("(A & (B | C)) | ((C | B) & A) | (A | B)", true);
+        assert_eq!(expression.redundant_subexpressions(), vec![(0, 2), (1, 3)]);
+
+        let mut expression = Expression::parse("(A & B) | (A | B)", true);
+        assert!(expression.redundant_subexpressions().is_empty());
+    }
+
+    #[test]
+    fn test_as_truth_integer() {
+        assert_eq!(Expression::parse("A & B", true).as_truth_integer(), 0b1000);
+        assert_eq!(Expression::parse("A | B", true).as_truth_integer(), 0b1110);
+        assert_eq!(Expression::parse("!A", true).as_truth_integer(), 0b01);
+        assert_eq!(
+            Expression::parse("A & B & C & D", true).as_truth_integer(),
+            0b1000_0000_0000_0000
+        );
+    }
+
+    #[test]
+    fn test_from_truth_integer() {
+        for proposition_count in 1..=3 {
+            for value in 0..(1u16 << (1 << proposition_count)) {
+                let mut expression = Expression::from_truth_integer(value, proposition_count);
+
+                assert_eq!(expression.proposition_count(), proposition_count);
+                assert_eq!(expression.as_truth_integer(), value);
+            }
+        }
+
+        let mut expression = Expression::parse("(A & !B) | (C & D)", true);
+        let value = expression.as_truth_integer();
+        assert_eq!(
+            Expression::from_truth_integer(value, 4).as_truth_integer(),
+            value
+        );
+    }
+
+    #[test]
+    fn test_is_tautology_fast() {
+        assert!(Expression::parse("A | !A", true).is_tautology_fast());
+        assert!(!Expression::parse("A | B", true).is_tautology_fast());
+
+        // The first permutation (all false) already disproves the tautology
+        let mut expression = Expression::parse("A | B | C | D", true);
+        assert_eq!(expression.find_permutation(false), (Some(0b0000), 1));
+
+        let mut expression = Expression::parse("A | !A", true);
+        assert_eq!(expression.find_permutation(false), (None, 2));
+    }
+
+    #[test]
+    fn test_is_contradiction_fast() {
+        assert!(Expression::parse("A & !A", true).is_contradiction_fast());
+        assert!(!Expression::parse("A | B", true).is_contradiction_fast());
+
+        // Only A is true in the second permutation, which already satisfies the expression
+        let mut expression = Expression::parse("A | B | C | D", true);
+        assert_eq!(expression.find_permutation(true), (Some(0b1000 << 28), 2));
+    }
+
+    #[test]
+    fn test_evaluate_xor() {
+        let mut expression = Expression::parse("A ^ B", true);
+        assert_eq!(expression.operators, vec![Operator::Xor]);
+
+        for (permutation, expected) in [
+            (0b0000, false),
+            (0b0100 << 28, true),
+            (0b1000 << 28, true),
+            (0b1100 << 28, false),
+        ] {
+            expression.set_values(permutation);
+            assert_eq!(expression.evaluate(), expected);
+        }
+
+        // Three-way XOR is true when an odd number of propositions are true
+        let mut expression = Expression::parse("A ^ B ^ C", true);
+
+        for permutation in get_bit_permutations(3) {
+            expression.set_values(permutation);
+            assert_eq!(expression.evaluate(), permutation.count_ones() % 2 == 1);
+        }
+    }
+
+    #[test]
+    fn test_xor_keyword() {
+        assert_eq!(
+            Expression::parse("A XOR B", true),
+            Expression::parse("A ^ B", true)
+        );
+        assert_eq!(
+            Expression::parse("a xor !(b XOR c)", true),
+            Expression::parse("A ^ !(B ^ C)", true)
+        );
+
+        // The keyword is only recognized as a whole word, so adjacent letters form the name of a proposition
+        assert_eq!(
+            Expression::try_parse("AXOR B", true),
+            Err(ParseError::MismatchedOperatorCount(5))
+        );
+        assert_eq!(
+            Expression::try_parse("A XORB", true),
+            Err(ParseError::MismatchedOperatorCount(2))
+        );
+    }
+
+    #[test]
+    fn test_stacked_negation() {
+        assert_eq!(Expression::parse("!!A", true), Expression::parse("A", true));
+        assert_eq!(
+            Expression::parse("!!!A", true),
+            Expression::parse("!A", true)
+        );
+        assert_eq!(
+            Expression::parse("not !A & \\neg \\neg B", true),
+            Expression::parse("A & B", true)
+        );
+
+        let mut expression = Expression::parse("!(A & B) & !(!C | D)", true);
+        let mut expected = Expression::parse("(!A | !B) & C & !D", true);
+        assert!(expression.is_equivalent_to(&mut expected));
+
+        let mut expression = Expression::parse("!!(A & !!B) | !!!C", true);
+        let mut expected = Expression::parse("(A & B) | !C", true);
+        assert!(expression.is_equivalent_to(&mut expected));
+    }
+
+    #[test]
+    fn test_word_operators() {
+        assert_eq!(
+            Expression::parse("A and not B or C", true),
+            Expression::parse("A & !B | C", true)
+        );
+        assert_eq!(
+            Expression::parse("NOT (A OR B) AND c", true),
+            Expression::parse("!(A | B) & C", true)
+        );
+        assert_eq!(
+            Expression::parse("A And B", true),
+            Expression::parse("A & B", true)
+        );
+
+        // Keywords must be separated from propositions
+        assert_eq!(
+            Expression::try_parse("NOTA", true),
+            Ok(Expression::var("NOTA"))
+        );
+        assert_eq!(
+            Expression::try_parse("NOTA B", true),
+            Err(ParseError::MismatchedOperatorCount(5))
+        );
+    }
+
+    #[test]
+    fn test_latex_operators() {
+        assert_eq!(
+            Expression::parse("A \\land (B \\lor \\neg C)", true),
+            Expression::parse("A & (B | !C)", true)
+        );
+        assert_eq!(
+            Expression::parse("A \\rightarrow B \\leftrightarrow \\lnot A \\oplus B", true),
+            Expression::parse("A -> B <-> !A ^ B", true)
+        );
+        assert_eq!(
+            Expression::parse("A\\wedge B\\vee C", true),
+            Expression::parse("A & B | C", true)
+        );
+
+        assert_eq!(
+            Expression::try_parse("A \\frac B", true),
+            Err(ParseError::InvalidCharacter('\\', 2))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_nand_nor() {
+        let mut nand = Expression::parse("A !& B", true);
+        let mut nor = Expression::parse("A !| B", true);
+        assert_eq!(nand.operators, vec![Operator::Nand]);
+        assert_eq!(nor.operators, vec![Operator::Nor]);
+        assert_eq!(nand, Expression::parse("A NAND B", true));
+        assert_eq!(nor, Expression::parse("a nor b", true));
+
+        for (permutation, expected_nand, expected_nor) in [
+            (0b0000, true, true),
+            (0b0100 << 28, true, false),
+            (0b1000 << 28, true, false),
+            (0b1100 << 28, false, false),
+        ] {
+            assert_eq!(nand.evaluate_permutation(permutation), expected_nand);
+            assert_eq!(nor.evaluate_permutation(permutation), expected_nor);
+        }
+
+        // NAND binds like AND and NOR binds like OR, and chains are evaluated left to right
+        assert_eq!(
+            Expression::parse("A !| B !& C", true),
+            Expression::parse("A !| (B !& C)", true)
+        );
+        assert!(Expression::parse("(A !& B !& C) <-> !(!(A & B) & C)", true).is_tautology_fast());
+
+        // A negation can still follow an operator
+        assert_eq!(
+            Expression::parse("A & !B", true).operators,
+            vec![Operator::And]
+        );
+    }
+
+    #[test]
+    fn test_evaluate_xnor() {
+        let mut expression = Expression::parse("A ~^ B", true);
+        assert_eq!(expression.operators, vec![Operator::Xnor]);
+
+        for (permutation, expected) in [
+            (0b0000, true),
+            (0b0100 << 28, false),
+            (0b1000 << 28, false),
+            (0b1100 << 28, true),
+        ] {
+            expression.set_values(permutation);
+            assert_eq!(expression.evaluate(), expected);
+        }
+
+        assert_eq!(
+            Expression::try_parse("A ~ B", true),
+            Err(ParseError::InvalidCharacter('~', 2))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_implies() {
+        let mut expression = Expression::parse("A -> B", true);
+        assert_eq!(expression.operators, vec![Operator::Implies]);
+
+        for (permutation, expected) in [
+            (0b0000, true),
+            (0b0100 << 28, true),
+            (0b1000 << 28, false),
+            (0b1100 << 28, true),
+        ] {
+            expression.set_values(permutation);
+            assert_eq!(expression.evaluate(), expected);
+        }
+
+        // Modus ponens is a tautology
+        assert!(Expression::parse("(A & (A -> B)) -> B", true).is_tautology_fast());
+
+        assert_eq!(
+            Expression::parse("A => B", true),
+            Expression::parse("A -> B", true)
+        );
+        assert_eq!(
+            Expression::try_parse("A = B", true),
+            Err(ParseError::InvalidCharacter('=', 2))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_iff() {
+        let mut expression = Expression::parse("A <-> B", true);
+        assert_eq!(expression.operators, vec![Operator::Iff]);
+
+        for (permutation, expected) in [
+            (0b0000, true),
+            (0b0100 << 28, false),
+            (0b1000 << 28, false),
+            (0b1100 << 28, true),
+        ] {
+            expression.set_values(permutation);
+            assert_eq!(expression.evaluate(), expected);
+        }
+
+        assert_eq!(
+            Expression::parse("A <=> B", true),
+            Expression::parse("A <-> B", true)
+        );
+
+        // Contraposition is a tautology
+        assert!(Expression::parse("(A -> B) <-> (!B -> !A)", true).is_tautology_fast());
+        assert!(Expression::parse("(A => B) <=> (!B => !A)", true).is_tautology_fast());
+
+        assert_eq!(
+            Expression::try_parse("A <> B", true),
+            Err(ParseError::InvalidCharacter('<', 2))
+        );
+        assert_eq!(
+            Expression::try_parse("A - B", true),
+            Err(ParseError::InvalidCharacter('-', 2))
+        );
+    }
+
+    #[test]
+    fn test_implies_right_associative() {
+        let mut chained = Expression::parse("A -> B -> C", true);
+        let mut right = Expression::parse("A -> (B -> C)", true);
+        let mut left = Expression::parse("(A -> B) -> C", true);
+
+        assert_eq!(chained, right);
+        assert_eq!(chained.as_truth_integer(), right.as_truth_integer());
+
+        // The groupings disagree when A and C are false
+        assert_ne!(chained.as_truth_integer(), left.as_truth_integer());
+        assert!(chained.evaluate_permutation(0b0000));
+        assert!(!left.evaluate_permutation(0b0000));
+
+        // Chains mixing looser and tighter operators only group the implications
+        assert_eq!(
+            Expression::parse("A -> B & C -> D", true),
+            Expression::parse("A -> ((B & C) -> D)", true)
+        );
+    }
+
+    #[test]
+    fn test_iff_right_associative() {
+        let mut chained = Expression::parse("A <-> B <-> C", true);
+        let mut left = Expression::parse("(A <-> B) <-> C", true);
+
+        assert_eq!(chained, Expression::parse("A <-> (B <-> C)", true));
+        assert_eq!(chained.to_string(), "A <-> B <-> C");
+
+        // Both groupings are equivalent, since the biconditional is associative
+        assert!(chained.is_equivalent_to(&mut left));
+    }
+
+    #[test]
+    fn test_precedence() {
+        assert_eq!(
+            Expression::parse("A | B & C", true),
+            Expression::parse("A | (B & C)", true)
+        );
+        assert_eq!(
+            Expression::parse("A & B | C & D", true),
+            Expression::parse("(A & B) | (C & D)", true)
+        );
+        assert_eq!(
+            Expression::parse("!A & B | !C", true),
+            Expression::parse("(!A & B) | !C", true)
+        );
+        assert_eq!(
+            Expression::parse("A | B ^ C & D", true),
+            Expression::parse("A | (B ^ (C & D))", true)
+        );
+        assert_eq!(
+            Expression::parse("A -> B <-> C | D", true),
+            Expression::parse("(A -> B) <-> (C | D)", true)
+        );
+
+        // Every level of the precedence ladder, from NOT down to the biconditional, agrees with explicit grouping
+        for (ungrouped, grouped) in [
+            ("!A & B", "(!A) & B"),
+            ("A & B ^ C", "(A & B) ^ C"),
+            ("A ^ B | C", "(A ^ B) | C"),
+            ("A | B -> C", "(A | B) -> C"),
+            ("A -> B <-> C", "(A -> B) <-> C"),
+            (
+                "A <-> B -> C | D ^ E & !F",
+                "A <-> (B -> (C | (D ^ (E & !F))))",
+            ),
+        ] {
+            let mut ungrouped = Expression::parse(ungrouped, true);
+            let mut grouped = Expression::parse(grouped, true);
+            assert!(ungrouped.is_equivalent_to(&mut grouped));
+        }
+
+        // Explicit parentheses still override precedence
+        let mut grouped = Expression::parse("(A | B) & C", true);
+        let mut ungrouped = Expression::parse("A | B & C", true);
+        assert_ne!(grouped.as_truth_integer(), ungrouped.as_truth_integer());
+        assert!(ungrouped.evaluate_permutation(0b1000 << 28));
+        assert!(!grouped.evaluate_permutation(0b1000 << 28));
+    }
+
+    #[test]
+    fn test_subexpression_label() {
+        let expression = Expression::parse("(A & !B) | !(C ^ (A -> D))", true);
+
+        assert_eq!(
+            expression.subexpression_label(&[0]),
+            Ok(String::from("(A & !B)"))
+        );
+        assert_eq!(
+            expression.subexpression_label(&[1]),
+            Ok(String::from("(C ^ (A -> D))"))
+        );
+        assert_eq!(
+            expression.subexpression_label(&[1, 1]),
+            Ok(String::from("(A -> D)"))
+        );
+    }
+
+    #[test]
+    fn test_evaluate_subexpression_permutation() {
+        let mut expression = Expression::parse("(A & B) | C", true);
+
+        assert_eq!(
+            expression.evaluate_subexpression_permutation(&[0], 0b0010 << 28),
+            Ok(false)
+        );
+        assert_eq!(
+            expression.evaluate_subexpression_permutation(&[0], 0b1100 << 28),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn test_subexpression_at_invalid_path() {
+        let mut expression = Expression::parse("(A & B) | C", true);
+        let error = InvalidSubexpressionPath(vec![1]);
+
+        assert_eq!(expression.subexpression_label(&[1]), Err(error));
+        assert_eq!(
+            expression.evaluate_subexpression_permutation(&[0, 0], 0),
+            Err(InvalidSubexpressionPath(vec![0, 0]))
+        );
+        assert_eq!(
+            expression
+                .subexpression_label(&[5])
+                .unwrap_err()
+                .to_string(),
+            "No subexpression exists at path [5]"
+        );
+    }
+
+    #[test]
+    fn test_get_subexpression_nested_single() {
+        let (tokens, _) = tokenize("((A | B) & C)", 0, &ParseOptions::default());
+        assert_eq!(get_subexpression(&tokens), Some(&tokens[1..8]));
+    }
+
+    #[test]
+    fn test_get_subexpression_nested_multi() {
+        let (tokens, _) = tokenize("((A | B) & C) & (D & C & A)", 0, &ParseOptions::default());
+        assert_eq!(get_subexpression(&tokens), Some(&tokens[1..8]));
+    }
+
+    #[test]
+    fn test_get_subexpression() {
+        let (tokens, _) = tokenize("(A | B & C)", 0, &ParseOptions::default());
+        assert_eq!(get_subexpression(&tokens), Some(&tokens[1..6]));
+    }
+
+    #[test]
+    fn test_leaf_count() {
+        let expression = Expression::parse("(A & A) | A", true);
+        assert_eq!(expression.leaf_count(), 3);
+        assert_eq!(expression.proposition_count(), 1);
+
+        let expression = Expression::parse("!(A | B) & (C -> (A ^ D))", true);
+        assert_eq!(expression.leaf_count(), 5);
+        assert_eq!(expression.proposition_count(), 4);
+    }
+
+    #[test]
+    fn test_is_equivalent_to() {
+        // De Morgan's laws
+        let mut left = Expression::parse("!(A & B)", true);
+        assert!(left.is_equivalent_to(&mut Expression::parse("!A | !B", true)));
+
+        let mut left = Expression::parse("!(A | B)", true);
+        assert!(left.is_equivalent_to(&mut Expression::parse("!A & !B", true)));
+        assert!(!left.is_equivalent_to(&mut Expression::parse("!A | !B", true)));
+
+        // Distributivity, which also depends on AND binding tighter than OR
+        let mut left = Expression::parse("A & (B | C)", true);
+        assert!(left.is_equivalent_to(&mut Expression::parse("A & B | A & C", true)));
+
+        let mut left = Expression::parse("A | B & C", true);
+        assert!(left.is_equivalent_to(&mut Expression::parse("(A | B) & (A | C)", true)));
+        assert!(!left.is_equivalent_to(&mut Expression::parse("(A | B) & C", true)));
+
+        // Different proposition counts are compared over the larger set
+        let mut left = Expression::parse("A", true);
+        assert!(left.is_equivalent_to(&mut Expression::parse("A & (B | !B)", true)));
+        assert!(!left.is_equivalent_to(&mut Expression::parse("A & B", true)));
+    }
+
+    #[test]
+    fn test_substitute() {
+        let mut expression = Expression::parse("A & !B", true);
+        let replacement = Expression::parse("B | C", false);
+
+        expression.substitute(PropositionIdentifier::from_char('B'), &replacement);
+        assert_eq!(expression.proposition_count(), 3);
+        assert_eq!(expression, Expression::parse("A & !(B | C)", true));
+
+        // Propositions which are no longer used are removed from the table
+        let mut expression = Expression::parse("(A | B) & A", true);
+        expression.substitute(
+            PropositionIdentifier::from_char('A'),
+            &Expression::parse("B", false),
+        );
+        assert_eq!(expression.proposition_count(), 1);
+
+        // Substituting a proposition checks the proposition count before changing anything
+        let names: Vec<String> = (0..26).map(|i| format!("p{}", i)).collect();
+        let mut expression = Expression::parse(&names.join(" & "), true);
+        let original = expression.clone();
+        assert_eq!(
+            expression.try_substitute(
+                PropositionIdentifier::from_name("p0"),
+                &Expression::parse("A | B", true)
+            ),
+            Err(ParseError::TooManyPropositions)
+        );
+        assert_eq!(expression, original);
+
+        // Replacing a proposition with one new name keeps the count the same
+        assert!(expression
+            .try_substitute(
+                PropositionIdentifier::from_name("p0"),
+                &Expression::parse("A", true)
+            )
+            .is_ok());
+        assert_eq!(expression.proposition_count(), 26);
+    }
+
+    #[test]
+    #[should_panic(expected = "Expression contains more than 26 propositions")]
+    fn test_built_too_many_propositions() {
+        let _ = (0..27)
+            .map(|i| Expression::var(&format!("p{}", i)))
+            .reduce(Expression::and);
+    }
+
+    #[test]
+    fn test_has_gaps() {
+        assert!(Expression::parse("A & C", false).has_gaps());
+        assert!(Expression::parse("B", false).has_gaps());
+        assert!(!Expression::parse("(A | C) & B", false).has_gaps());
+
+        // Substitutions can introduce gaps into a valid expression
+        let mut expression = Expression::parse("A & B", true);
+        expression.substitute(
+            PropositionIdentifier::from_char('B'),
+            &Expression::parse("C", false),
+        );
+        assert!(expression.has_gaps());
+    }
+
+    #[test]
+    fn test_compacted() {
+        let mut expression = Expression::parse("(A & B) | (A & !B)", true);
+        assert_eq!(
+            expression.irrelevant_variables(),
+            vec![PropositionIdentifier::from_char('B')]
+        );
+        assert_eq!(expression.compacted(), Expression::parse("A", true));
+
+        // The remaining variables are renamed starting from A
+        let mut expression = Expression::parse("(A & !A) | (B & !C) | (C & !C)", true);
+        let mut compacted = expression.compacted();
+        assert_eq!(compacted.proposition_count(), 2);
+        assert!(compacted.is_equivalent_to(&mut Expression::parse("A & !B", true)));
+
+        let mut expression = Expression::parse("(A | !A) | B", true);
+        assert_eq!(expression.compacted(), Expression::constant(true));
+
+        let mut expression = Expression::parse("A & !A", true);
+        let mut compacted = expression.compacted();
+        assert_eq!(compacted, Expression::constant(false));
+        assert_eq!(compacted.proposition_count(), 0);
+        assert!(!compacted.evaluate_permutation(0));
+    }
+
+    #[test]
+    fn test_permutation_from_assignments() {
+        let mut expression = Expression::parse("A & (B | C)", true);
+
+        let permutation = expression
+            .permutation_from_assignments("A=1,B=0,C=1")
+            .unwrap();
+        assert_eq!(permutation, 0b1010 << 28);
+        assert!(expression.evaluate_permutation(permutation));
+
+        let permutation = expression
+            .permutation_from_assignments("c=0, a=1, b=0")
+            .unwrap();
+        assert!(!expression.evaluate_permutation(permutation));
+
+        use AssignmentError::*;
+
+        assert_eq!(
+            expression.permutation_from_assignments("A=1,B=0"),
+            Err(MissingProposition(String::from("C")))
+        );
+        assert_eq!(
+            expression.permutation_from_assignments("A=1,B=0,C=1,D=1"),
+            Err(UnusedProposition(String::from("D")))
+        );
+        assert_eq!(
+            expression.permutation_from_assignments("A=1,A=0,B=0,C=1"),
+            Err(DuplicateProposition(String::from("A")))
+        );
+        assert_eq!(
+            expression.permutation_from_assignments("A=1,B=2,C=1"),
+            Err(InvalidAssignment(String::from("B=2")))
+        );
+        assert_eq!(
+            expression.permutation_from_assignments("AB=1"),
+            Err(UnusedProposition(String::from("AB")))
+        );
+        assert_eq!(
+            expression.permutation_from_assignments("1A=1"),
+            Err(InvalidAssignment(String::from("1A=1")))
+        );
+
+        // Propositions with longer names are assigned by their names
+        let mut expression = Expression::parse("rain & !is_admin | A", true);
+        let permutation = expression
+            .permutation_from_assignments("rain=1, is_admin=0, A=0")
+            .unwrap();
+        assert_eq!(permutation, 0b001 << 29);
+        assert!(expression.evaluate_permutation(permutation));
+    }
+
+    #[test]
+    fn test_constants() {
+        let mut expression = Expression::parse("A & 0", true);
+        assert_eq!(expression.proposition_count(), 1);
+        assert!(expression.is_contradiction_fast());
+
+        let mut expression = Expression::parse("A | 1", true);
+        assert_eq!(expression.proposition_count(), 1);
+        assert!(expression.is_tautology_fast());
+
+        // Expressions of only constants have no propositions, and are still valid
+        let mut expression = Expression::try_parse("1", true).unwrap();
+        assert_eq!(expression.proposition_count(), 0);
+        assert!(expression.evaluate_permutation(0));
+
+        let mut expression = Expression::try_parse("0 | 1", true).unwrap();
+        assert!(!expression.has_gaps());
+        assert_eq!(
+            TruthTable::from_expression(&mut expression).to_minterm_notation(),
+            "f() = Σm(0)"
+        );
+
+        // Constants can be negated like propositions
+        let mut expression = Expression::parse("!0 & (B | !1) & A", true);
+        assert_eq!(expression.proposition_count(), 2);
+        assert!(expression.is_equivalent_to(&mut Expression::parse("A & B", true)));
+
+        assert_eq!(
+            Expression::parse("0 | (A & 1)", true).subexpression_label(&[]),
+            Ok(String::from("(0 | A & 1)"))
+        );
+    }
+
+    #[test]
+    fn test_labeled_subexpression_values() {
+        let mut expression = Expression::parse("((A & B) | C) & !(B ^ C)", true);
+        assert_eq!(
+            expression.subexpression_paths(),
+            vec![vec![0, 0], vec![0], vec![1]]
+        );
+
+        assert_eq!(
+            expression.labeled_subexpression_values(0b1100 << 28),
+            vec![
+                (String::from("(A & B)"), true),
+                (String::from("(A & B | C)"), true),
+                (String::from("(B ^ C)"), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_wrapped() {
+        let expected = Expression::parse("A & B", true);
+
+        assert_eq!(Expression::parse("\"A & B\"", true), expected);
+        assert_eq!(Expression::parse("'A & B'", true), expected);
+        assert_eq!(Expression::parse("\u{FEFF}A & B", true), expected);
+        assert_eq!(Expression::parse("\u{FEFF}\"A & B\"\n", true), expected);
+
+        // Unbalanced quotes are still rejected
+        assert_eq!(
+            Expression::try_parse("\"A & B", true),
+            Err(ParseError::InvalidCharacter('"', 0))
+        );
+    }
+
+    #[test]
+    fn test_parenthesis_positions() {
+        use ParseError::*;
+
+        assert_eq!(
+            Expression::try_parse("(A & B", true),
+            Err(UnclosedParenthesis(0))
+        );
+        assert_eq!(
+            Expression::try_parse("A & B)", true),
+            Err(UnmatchedParenthesis(5))
+        );
+        assert_eq!(
+            Expression::try_parse("((A)", true),
+            Err(UnclosedParenthesis(0))
+        );
+
+        assert_eq!(
+            Expression::try_parse("(A)) & B", true),
+            Err(UnmatchedParenthesis(3))
+        );
+        assert_eq!(
+            Expression::try_parse("(A) & (B", true),
+            Err(UnclosedParenthesis(6))
+        );
+        assert_eq!(
+            Expression::try_parse("(A & (B) | C", true),
+            Err(UnclosedParenthesis(0))
+        );
+
+        // Positions inside subexpressions and quoted input are relative to the whole string
+        assert_eq!(
+            Expression::try_parse("A & (B | (C & D)", true),
+            Err(UnclosedParenthesis(4))
+        );
+        assert_eq!(
+            Expression::try_parse("A & (B | (C & (D))", true),
+            Err(UnclosedParenthesis(4))
+        );
+        assert_eq!(
+            Expression::try_parse("\"A & B)\"", true),
+            Err(UnmatchedParenthesis(6))
+        );
+
+        let (tokens, _) = tokenize("(A & (B)", 0, &ParseOptions::default());
+        assert_eq!(get_subexpression(&tokens), None);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        assert_eq!(
+            Expression::parse("(!A & B) | (C & D)", true).to_string(),
+            "!A & B | C & D"
+        );
+        assert_eq!(
+            Expression::parse("!A&B|C   &D", true).to_string(),
+            "!A & B | C & D"
+        );
+
+        // Parentheses are kept where precedence or associativity requires them
+        for input in [
+            "(A | B) & C",
+            "A & (B !& C)",
+            "(A -> B) -> C",
+            "!(A & B) ^ 1",
+        ] {
+            assert_eq!(Expression::parse(input, true).to_string(), input);
+        }
+
+        for input in [
+            "A -> B -> C",
+            "!(A ^ B) <-> (C ~^ !D)",
+            "A & 1 | !(B & (C | 0))",
+        ] {
+            let expression = Expression::parse(input, true);
+            let reparsed = Expression::parse(&expression.to_string(), true);
+
+            assert_eq!(
+                TruthTable::parse_expression_str(input),
+                TruthTable::parse_expression_str(&reparsed.to_string())
+            );
+            assert_eq!(reparsed, expression);
+        }
+    }
+
+    #[test]
+    fn test_gaps_use_only_present_propositions() {
+        let mut expression = Expression::parse("A & C", false);
+        assert_eq!(expression.as_truth_integer(), 0b1000);
+        // Permutations are over the propositions which are present, so A and C take the two highest bits
+        assert_eq!(expression.find_permutation(true), (Some(0b11 << 30), 4));
+        assert!(expression.irrelevant_variables().is_empty());
+
+        let mut other = Expression::parse("C & (A | B) & (A | !B)", false);
+        assert!(expression.is_equivalent_to(&mut other));
     }
 }
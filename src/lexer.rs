@@ -0,0 +1,602 @@
+use std::ops::Range;
+
+use crate::expressions::Operator;
+use crate::propositions::is_name_char;
+use crate::ParseError;
+use crate::ParseOptions;
+use crate::PropositionIdentifier;
+use crate::SyntaxProfile;
+
+// Represents a single token of an expression, along with the byte range of the expression string it was read from
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Token {
+    pub kind: TokenKind,
+    pub span: Range<usize>,
+}
+
+// Represents the kind of a token
+// In postfix notation, a negation applies to the operand before it
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum TokenKind {
+    Proposition(PropositionIdentifier),
+    Constant(bool),
+    Negation,
+    Operator(Operator),
+    OpenParenthesis,
+    CloseParenthesis,
+    // A use of the definition at the given index, which only uses the definitions before it
+    Definition(usize),
+}
+
+// Represents a definition such as "X := A & B" from the prelude of an expression
+// Each use of its name is a single token, so that the definition is only parsed once however often it is used
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) struct Definition {
+    // The position of the name of the definition
+    pub position: usize,
+    pub tokens: Vec<Token>,
+    // The position where the definition ends, which is used for errors when its tokens run out
+    pub end: usize,
+}
+
+// The most tokens that can be copied into an expression or definition by replacing each use of a definition with its
+// tokens, since definitions which use each other several times could otherwise grow exponentially
+const MAX_COPIED_LENGTH: usize = 1 << 16;
+
+impl Token {
+    fn new(kind: TokenKind, span: Range<usize>) -> Self {
+        Self { kind, span }
+    }
+}
+
+// Splits an expression into tokens, where each span is shifted by the offset of the expression in its original string
+// Invalid characters and words are collected as errors and skipped, so that every error can be reported at once
+// Tokens only need to be separated by whitespace where they would otherwise form a single word, such as "A B",
+// unless implicit AND is enabled, in which case a word of letters and constants is split into one token per character
+// Any other word which is not a keyword is the name of a proposition, such as "rain" or "is_admin"
+// Operators and negation are only accepted in the spellings of the syntax profile
+pub(crate) fn tokenize(
+    expression: &str,
+    offset: usize,
+    options: &ParseOptions,
+) -> (Vec<Token>, Vec<ParseError>) {
+    tokenize_with_names(expression, offset, options, &[])
+}
+
+// Splits an expression after a prelude of definitions such as "X := A & B; Y := !C | D;" into tokens, along with the
+// tokens of each definition
+// Definitions can use each other in any order, as long as none of them uses itself, and they are returned in an order
+// where each one only uses the definitions before it
+// Names are matched ignoring case like propositions, and if a name is defined twice, its first definition is used
+pub(crate) fn tokenize_with_definitions(
+    expression: &str,
+    offset: usize,
+    options: &ParseOptions,
+) -> (Vec<Token>, Vec<Definition>, Vec<ParseError>) {
+    let mut names = Vec::new();
+    let mut bodies = Vec::new();
+    let mut errors = Vec::new();
+
+    let mut statements = split_statements(expression, options);
+    let (main_start, main) = statements.pop().unwrap();
+
+    for (start, statement) in statements {
+        // Definitions can be preceded by comments
+        let skipped = skip_comments(statement, options);
+        let (start, statement) = (start + skipped, &statement[skipped..]);
+
+        match statement.split_once(":=") {
+            Some((name, body)) if is_definition_name(name.trim(), options) => {
+                names.push(name.trim());
+                bodies.push((offset + start, offset + start + name.len() + 2, body));
+            }
+            _ => errors.push(ParseError::InvalidDefinition(offset + start)),
+        }
+    }
+
+    // Every name is known before any body is read, so that definitions can use the ones after them
+    let definitions: Vec<Definition> = bodies
+        .into_iter()
+        .map(|(position, body_offset, body)| {
+            let (tokens, mut body_errors) = tokenize_with_names(body, body_offset, options, &names);
+            errors.append(&mut body_errors);
+
+            Definition {
+                position,
+                tokens,
+                end: body_offset + body.len(),
+            }
+        })
+        .collect();
+
+    let (mut tokens, mut main_errors) =
+        tokenize_with_names(main, offset + main_start, options, &names);
+    errors.append(&mut main_errors);
+
+    let (order, lengths) = match order_definitions(&definitions) {
+        Ok(ordered) => ordered,
+        Err(error) => {
+            errors.push(error);
+            return (tokens, definitions, errors);
+        }
+    };
+
+    if copied_length(&tokens, &lengths) > MAX_COPIED_LENGTH {
+        errors.push(ParseError::ExpansionTooLarge);
+    }
+
+    // Renumber every use of a definition to match its new index
+    let mut indices = vec![0; order.len()];
+
+    for (new, old) in order.iter().enumerate() {
+        indices[*old] = new;
+    }
+
+    let mut definitions: Vec<Option<Definition>> = definitions.into_iter().map(Some).collect();
+    let mut ordered: Vec<Definition> = order
+        .iter()
+        .map(|index| definitions[*index].take().unwrap())
+        .collect();
+
+    for token in ordered
+        .iter_mut()
+        .flat_map(|definition| definition.tokens.iter_mut())
+        .chain(tokens.iter_mut())
+    {
+        if let TokenKind::Definition(index) = &mut token.kind {
+            *index = indices[*index];
+        }
+    }
+
+    (tokens, ordered, errors)
+}
+
+// Finds an order in which each definition comes after every definition it uses, along with the number of tokens
+// each definition has once every use of another definition in it is replaced by its tokens
+// Returns an error if a definition uses itself, either directly or through other definitions, or copies too many
+// tokens
+fn order_definitions(definitions: &[Definition]) -> Result<(Vec<usize>, Vec<usize>), ParseError> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        InProgress,
+        Done,
+    }
+
+    let uses: Vec<Vec<usize>> = definitions
+        .iter()
+        .map(|definition| {
+            definition
+                .tokens
+                .iter()
+                .filter_map(|token| match token.kind {
+                    TokenKind::Definition(index) => Some(index),
+                    _ => None,
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut states = vec![State::Unvisited; definitions.len()];
+    let mut lengths = vec![0; definitions.len()];
+    let mut order = Vec::new();
+
+    // The definitions are searched depth first with an explicit stack, since chains of them can be long
+    for root in 0..definitions.len() {
+        if states[root] != State::Unvisited {
+            continue;
+        }
+
+        states[root] = State::InProgress;
+        let mut stack = vec![(root, 0)];
+
+        while let Some((index, next)) = stack.last_mut() {
+            let index = *index;
+
+            if let Some(used) = uses[index].get(*next).copied() {
+                *next += 1;
+
+                match states[used] {
+                    State::InProgress => {
+                        return Err(ParseError::CyclicDefinition(definitions[used].position))
+                    }
+                    State::Unvisited => {
+                        states[used] = State::InProgress;
+                        stack.push((used, 0));
+                    }
+                    State::Done => (),
+                }
+            } else {
+                stack.pop();
+                states[index] = State::Done;
+
+                let tokens = &definitions[index].tokens;
+                let copied = copied_length(tokens, &lengths);
+
+                if copied > MAX_COPIED_LENGTH {
+                    return Err(ParseError::ExpansionTooLarge);
+                }
+
+                lengths[index] = tokens.len() + copied;
+
+                order.push(index);
+            }
+        }
+    }
+
+    Ok((order, lengths))
+}
+
+// Counts the tokens copied into a sequence by replacing every use of a definition with its tokens in parentheses
+fn copied_length(tokens: &[Token], lengths: &[usize]) -> usize {
+    tokens
+        .iter()
+        .filter_map(|token| match token.kind {
+            TokenKind::Definition(index) => Some(lengths[index] + 2),
+            _ => None,
+        })
+        .sum()
+}
+
+// Splits an expression into tokens, where each use of one of the given names is a token of the definition with the
+// same index
+fn tokenize_with_names(
+    expression: &str,
+    offset: usize,
+    options: &ParseOptions,
+    names: &[&str],
+) -> (Vec<Token>, Vec<ParseError>) {
+    let mut tokens = Vec::new();
+    let mut errors = Vec::new();
+    let mut input_chars = expression.char_indices().peekable();
+
+    while let Some((i, c)) = input_chars.next() {
+        let start = offset + i;
+
+        // Symbols are matched first, so that aliases can take over characters such as '#'
+        let kind = if let Some((length, kind)) = options.symbol_at(&expression[i..]) {
+            // The first character of the symbol has already been read
+            while input_chars.next_if(|(j, _)| *j < i + length).is_some() {}
+
+            kind
+        } else {
+            match c {
+                // Words are read as a whole, since operators and negation can also be written as keywords such as
+                // "AND" and "NOT", and propositions can have names longer than a single letter
+                'A'..='Z' | 'a'..='z' | '_' => {
+                    let mut word = String::from(c);
+
+                    while let Some((_, next)) = input_chars.next_if(|(_, next)| is_name_char(*next))
+                    {
+                        word.push(next);
+                    }
+
+                    let definition = names
+                        .iter()
+                        .position(|name| name.eq_ignore_ascii_case(&word));
+
+                    if let Some(index) = definition {
+                        TokenKind::Definition(index)
+                    } else if let Some(kind) = options.keyword(&word) {
+                        kind
+                    } else if options.implicit_and
+                        && word.len() > 1
+                        && word
+                            .chars()
+                            .all(|c| c.is_ascii_alphabetic() || c == '0' || c == '1')
+                    {
+                        // Words only contain ASCII characters, so each character is one byte long
+                        for (j, c) in word.char_indices() {
+                            let kind = match c {
+                                '0' | '1' => TokenKind::Constant(c == '1'),
+                                _ => TokenKind::Proposition(proposition(&word[j..j + 1], options)),
+                            };
+
+                            tokens.push(Token::new(kind, start + j..start + j + 1));
+                        }
+
+                        continue;
+                    } else {
+                        TokenKind::Proposition(proposition(&word, options))
+                    }
+                }
+                // Constants are written as 0 (false) and 1 (true)
+                '0' | '1' => TokenKind::Constant(c == '1'),
+                // LaTeX commands such as "\land" and "\neg" are read as a whole word after the backslash
+                '\\' if options.syntax.accepts_latex() => {
+                    let mut command = String::new();
+
+                    while let Some((_, next)) =
+                        input_chars.next_if(|(_, next)| next.is_ascii_alphabetic())
+                    {
+                        command.push(next);
+                    }
+
+                    match SyntaxProfile::latex_command(&command) {
+                        Some(kind) => kind,
+                        None => {
+                            errors.push(ParseError::InvalidCharacter(c, start));
+                            continue;
+                        }
+                    }
+                }
+                '(' => TokenKind::OpenParenthesis,
+                ')' => TokenKind::CloseParenthesis,
+                // Comments run from '#' to the end of the line, so that expression files can be annotated
+                '#' => {
+                    while input_chars.next_if(|(_, next)| *next != '\n').is_some() {}
+                    continue;
+                }
+                c if c.is_whitespace() => continue,
+                _ => {
+                    errors.push(ParseError::InvalidCharacter(c, start));
+                    continue;
+                }
+            }
+        };
+
+        // The end of the token is wherever the next token could start
+        let end = input_chars
+            .peek()
+            .map_or(expression.len(), |(next, _)| *next);
+
+        tokens.push(Token::new(kind, start..offset + end));
+    }
+
+    (tokens, errors)
+}
+
+// Gets the proposition with the given name, where single letters are only folded to uppercase if case-sensitive mode
+// is off
+fn proposition(name: &str, options: &ParseOptions) -> PropositionIdentifier {
+    let identifier = if options.case_sensitive {
+        PropositionIdentifier::try_from_name_case_sensitive(name)
+    } else {
+        PropositionIdentifier::try_from_name(name)
+    };
+
+    identifier
+        .unwrap_or_else(|| unreachable!("[INTERNAL ERROR] Invalid proposition name '{}'", name))
+}
+
+// Splits an expression on each ';' outside of comments, returning each statement along with its position
+// There is always at least one statement, which is the main expression after any definitions
+fn split_statements<'a>(expression: &'a str, options: &ParseOptions) -> Vec<(usize, &'a str)> {
+    let mut statements = Vec::new();
+    let mut start = 0;
+    let mut in_comment = false;
+
+    for (i, c) in expression.char_indices() {
+        match c {
+            // '#' only starts a comment if it has not been given as an alias
+            '#' if options.symbol_at(&expression[i..]).is_none() => in_comment = true,
+            '\n' => in_comment = false,
+            ';' if !in_comment => {
+                statements.push((start, &expression[start..i]));
+                start = i + 1;
+            }
+            _ => (),
+        }
+    }
+
+    statements.push((start, &expression[start..]));
+
+    statements
+}
+
+// Returns the length of the whitespace and comments at the start of a statement
+fn skip_comments(statement: &str, options: &ParseOptions) -> usize {
+    let mut rest = statement.trim_start();
+
+    while rest.starts_with('#') && options.symbol_at(rest).is_none() {
+        rest = rest.find('\n').map_or("", |i| rest[i..].trim_start());
+    }
+
+    statement.len() - rest.len()
+}
+
+// Checks whether a word can be given a definition, which it cannot if it is already an operator
+fn is_definition_name(name: &str, options: &ParseOptions) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && options.keyword(name).is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_spans() {
+        let (tokens, errors) = tokenize("!A <-> (B\\land 1)", 2, &ParseOptions::default());
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::new(TokenKind::Negation, 2..3),
+                Token::new(
+                    TokenKind::Proposition(PropositionIdentifier::from_char('A')),
+                    3..4
+                ),
+                Token::new(TokenKind::Operator(Operator::Iff), 5..8),
+                Token::new(TokenKind::OpenParenthesis, 9..10),
+                Token::new(
+                    TokenKind::Proposition(PropositionIdentifier::from_char('B')),
+                    10..11
+                ),
+                Token::new(TokenKind::Operator(Operator::And), 11..16),
+                Token::new(TokenKind::Constant(true), 17..18),
+                Token::new(TokenKind::CloseParenthesis, 18..19),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_errors() {
+        let (tokens, errors) = tokenize("A ? rain ~", 0, &ParseOptions::default());
+
+        assert_eq!(tokens.len(), 2);
+        assert_eq!(
+            errors,
+            vec![
+                ParseError::InvalidCharacter('?', 2),
+                ParseError::InvalidCharacter('~', 9),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_names() {
+        let (tokens, errors) = tokenize("rain&p1 | _x2 AND NOTA", 0, &ParseOptions::default());
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens,
+            vec![
+                Token::new(
+                    TokenKind::Proposition(PropositionIdentifier::from_name("rain")),
+                    0..4
+                ),
+                Token::new(TokenKind::Operator(Operator::And), 4..5),
+                Token::new(
+                    TokenKind::Proposition(PropositionIdentifier::from_name("p1")),
+                    5..7
+                ),
+                Token::new(TokenKind::Operator(Operator::Or), 8..9),
+                Token::new(
+                    TokenKind::Proposition(PropositionIdentifier::from_name("_x2")),
+                    10..13
+                ),
+                Token::new(TokenKind::Operator(Operator::And), 14..17),
+                Token::new(
+                    TokenKind::Proposition(PropositionIdentifier::from_name("NOTA")),
+                    18..22
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_comments() {
+        let (tokens, errors) = tokenize("A # first ? (\n\n& B#", 0, &ParseOptions::default());
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.span.clone())
+                .collect::<Vec<_>>(),
+            vec![0..1, 15..16, 17..18]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_syntax() {
+        let options = ParseOptions {
+            syntax: SyntaxProfile::Math,
+            ..ParseOptions::default()
+        };
+        let (tokens, errors) = tokenize("¬A ∧ B & C", 0, &options);
+
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.span.clone())
+                .collect::<Vec<_>>(),
+            vec![0..2, 2..3, 4..7, 8..9, 12..13]
+        );
+        assert_eq!(errors, vec![ParseError::InvalidCharacter('&', 10)]);
+    }
+
+    #[test]
+    fn test_tokenize_with_definitions() {
+        let kinds = |tokens: &[Token]| {
+            tokens
+                .iter()
+                .map(|token| token.kind.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let (tokens, definitions, errors) = tokenize_with_definitions(
+            "X := A & B; carry := X | C; !carry",
+            0,
+            &ParseOptions::default(),
+        );
+        let (expected, _) = tokenize("A & B", 0, &ParseOptions::default());
+
+        // Each use of a name is a single token, rather than a copy of its definition
+        assert!(errors.is_empty());
+        assert_eq!(
+            kinds(&tokens),
+            vec![TokenKind::Negation, TokenKind::Definition(1)]
+        );
+        assert_eq!(kinds(&definitions[0].tokens), kinds(&expected));
+        assert_eq!(
+            kinds(&definitions[1].tokens),
+            vec![
+                TokenKind::Definition(0),
+                TokenKind::Operator(Operator::Or),
+                TokenKind::Proposition(PropositionIdentifier::from_char('C')),
+            ]
+        );
+
+        // Tokens from definitions keep their own spans, and uses of a name take its span
+        assert_eq!(tokens[1].span, 29..34);
+        assert_eq!(definitions[0].tokens[0].span, 5..6);
+        assert_eq!(definitions[1].tokens[0].span, 21..22);
+        assert_eq!((definitions[1].position, definitions[1].end), (12, 26));
+
+        // Definitions can use the ones after them, and are ordered so that they only use the ones before them
+        let (tokens, definitions, errors) = tokenize_with_definitions(
+            "carry := X | C; X := A & B; !carry",
+            0,
+            &ParseOptions::default(),
+        );
+
+        assert!(errors.is_empty());
+        assert_eq!(tokens[1].kind, TokenKind::Definition(1));
+        assert_eq!(definitions[0].position, 16);
+        assert_eq!(definitions[1].tokens[0].kind, TokenKind::Definition(0));
+
+        for (expression, position) in [("X := Y & A; Y := !X; X", 0), ("A; X := X | A; X", 3)] {
+            let (_, _, errors) = tokenize_with_definitions(expression, 0, &ParseOptions::default());
+
+            assert_eq!(errors.last(), Some(&ParseError::CyclicDefinition(position)));
+        }
+
+        let (_, _, errors) = tokenize_with_definitions(
+            "# Prelude; of definitions\nA & B; NOT := C; X := ?; X",
+            0,
+            &ParseOptions::default(),
+        );
+
+        assert_eq!(
+            errors,
+            vec![
+                ParseError::InvalidDefinition(26),
+                ParseError::InvalidDefinition(33),
+                ParseError::InvalidCharacter('?', 48),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tokenize_implicit_and() {
+        let options = ParseOptions {
+            implicit_and: true,
+            ..ParseOptions::default()
+        };
+        let (tokens, errors) = tokenize("AB1", 0, &options);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            tokens
+                .iter()
+                .map(|token| token.span.clone())
+                .collect::<Vec<_>>(),
+            vec![0..1, 1..2, 2..3]
+        );
+        assert_eq!(tokens[2].kind, TokenKind::Constant(true));
+    }
+}
@@ -0,0 +1,29 @@
+mod expressions;
+mod lexer;
+mod minimization;
+mod propositions;
+mod syntax;
+mod truth_table;
+
+pub use expressions::AssignmentError;
+pub use expressions::Expression;
+pub use expressions::InvalidSubexpressionPath;
+pub use expressions::ParseError;
+pub use expressions::ParseOptions;
+pub use propositions::InvalidProposition;
+pub use propositions::PropositionIdentifier;
+pub use propositions::PropositionTable;
+pub use syntax::AliasError;
+pub use syntax::OperatorAliases;
+pub use syntax::SyntaxProfile;
+pub use syntax::UnknownSyntaxProfile;
+pub use truth_table::closest_expression;
+pub use truth_table::ComparisonError;
+pub use truth_table::GateKind;
+pub use truth_table::MintermParseError;
+pub use truth_table::MissingProposition;
+pub use truth_table::RowOutOfRange;
+pub use truth_table::RowParseError;
+pub use truth_table::TableStyle;
+pub use truth_table::TooManyTerms;
+pub use truth_table::TruthTable;
@@ -1,28 +1,413 @@
-mod expressions;
-mod propositions;
-mod truth_table;
+use std::fmt::Display;
+use std::io::BufRead;
+use std::io::IsTerminal;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::time::Instant;
 
-pub use expressions::Expression;
-pub use propositions::PropositionIdentifier;
-pub use propositions::PropositionTable;
-
-use crate::truth_table::TruthTable;
+use truth_tables::Expression;
+use truth_tables::OperatorAliases;
+use truth_tables::ParseError;
+use truth_tables::ParseOptions;
+use truth_tables::SyntaxProfile;
+use truth_tables::TruthTable;
 
 fn main() {
     let args: Vec<String> = std::env::args().collect();
 
-    if args.len() != 3 {
-        println!(
-            "Usage: {} [-e | --expression] [-t | --truth-table] <input>",
-            args[0]
-        );
-        println!("Note: The flag you choose determines the input type, not the output type");
-        std::process::exit(1);
+    // The timing flag can be given anywhere, so remove it before matching the other arguments
+    let timing = args.iter().any(|arg| arg == "--timing");
+    let mut args: Vec<String> = args.into_iter().filter(|arg| arg != "--timing").collect();
+
+    // So can the syntax profile, which otherwise comes from the config file, and the maximum nesting depth, which is
+    // clamped so that it cannot overflow the stack
+    let syntax = take_syntax(&mut args);
+    let max_depth = take_flag_value(&mut args, "--max-depth").map(|depth| {
+        depth
+            .parse::<usize>()
+            .unwrap_or_else(|_| {
+                exit_with_error(format!("Invalid maximum nesting depth '{}'", depth))
+            })
+            .min(Expression::MAX_NESTING_DEPTH)
+    });
+    let options = ParseOptions {
+        syntax,
+        max_depth: max_depth.unwrap_or(ParseOptions::default().max_depth),
+        aliases: read_aliases(),
+        ..ParseOptions::default()
+    };
+
+    // Long expressions can be read from stdin when given as "-", or from a file given with -f, which is then handled
+    // the same as -e
+    match args.get(1).map(String::as_str) {
+        Some("-e" | "--expression") => {
+            if let Some(input) = args.get_mut(2) {
+                if input == "-" {
+                    *input = read_stdin();
+                }
+            }
+        }
+        Some("-f" | "--file") => {
+            if let Some(path) = args.get(2) {
+                args[2] = read_file(path);
+                args[1] = String::from("-e");
+            }
+        }
+        _ => (),
     }
 
-    match args[1].as_str() {
-        "-e" | "--expression" => TruthTable::parse_expression_str(&args[2]).print(),
-        "-t" | "--truth-table" => println!("{}", TruthTable::parse_rows(&args[2]).to_disjunction()),
-        _ => panic!("Illegal input formatting based on given flag"),
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args[1..] {
+        ["-e" | "--expression", input] => {
+            run_input(InputMode::Expression, input, &options, timing)
+                .unwrap_or_else(|error| exit_with_error(error));
+        }
+        ["-e" | "--expression", input, "--allow-gaps"] => {
+            let options = ParseOptions {
+                validate_propositions: false,
+                ..options
+            };
+
+            let table = tabulate(timing, || {
+                Expression::try_parse_with_options(input, &options)
+            });
+            timed(timing, "Output", || table.print());
+        }
+        ["-e" | "--expression", input, "--implicit-and"] => {
+            let options = ParseOptions {
+                implicit_and: true,
+                ..options
+            };
+
+            let table = tabulate(timing, || {
+                Expression::try_parse_with_options(input, &options)
+            });
+            timed(timing, "Output", || table.print());
+        }
+        ["-e" | "--expression", input, "--prefix"] => {
+            let table = tabulate(timing, || {
+                Expression::try_parse_prefix_with_options(input, &options)
+            });
+            timed(timing, "Output", || table.print());
+        }
+        ["-e" | "--expression", input, "--postfix"] => {
+            let table = tabulate(timing, || {
+                Expression::try_parse_postfix_with_options(input, &options)
+            });
+            timed(timing, "Output", || table.print());
+        }
+        ["-e" | "--expression", input, "--only-true"] => {
+            let table = tabulate(timing, || {
+                Expression::try_parse_with_options(input, &options)
+            });
+            timed(timing, "Output", || table.print_filtered(true));
+        }
+        ["-e" | "--expression", input, "--only-false"] => {
+            let table = tabulate(timing, || {
+                Expression::try_parse_with_options(input, &options)
+            });
+            timed(timing, "Output", || table.print_filtered(false));
+        }
+        ["-e" | "--expression", input, "--verbose"] => {
+            let table = tabulate_with(
+                timing,
+                || Expression::try_parse_with_options(input, &options),
+                TruthTable::from_expression_verbose,
+            );
+            timed(timing, "Output", || table.print());
+        }
+        ["-e" | "--expression", input, "--row", index] => {
+            let index = index
+                .parse()
+                .unwrap_or_else(|_| exit_with_error(format!("Invalid row index '{}'", index)));
+
+            let table = tabulate(timing, || {
+                Expression::try_parse_with_options(input, &options)
+            });
+            timed(timing, "Output", || table.try_print_row(index))
+                .unwrap_or_else(|error| exit_with_error(error));
+        }
+        ["-t" | "--truth-table", input] => {
+            run_input(InputMode::TruthTable, input, &options, timing)
+                .unwrap_or_else(|error| exit_with_error(error));
+        }
+        ["-t" | "--truth-table", input, "--vars", count] => {
+            let count = count.parse().unwrap_or_else(|_| {
+                exit_with_error(format!("Invalid proposition count '{}'", count))
+            });
+
+            match TruthTable::try_parse_rows_with_count(input, count) {
+                Ok(table) => print_disjunction(&table, syntax),
+                Err(error) => exit_with_error(error),
+            }
+        }
+        ["-m" | "--minterms", count, input] => {
+            let count = count.parse().unwrap_or_else(|_| {
+                exit_with_error(format!("Invalid proposition count '{}'", count))
+            });
+
+            match TruthTable::try_parse_minterms(input, count) {
+                Ok(table) => print_disjunction(&table, syntax),
+                Err(error) => exit_with_error(error),
+            }
+        }
+        ["--eval", input, assignments] => {
+            let mut expression = Expression::try_parse_with_options(input, &options)
+                .unwrap_or_else(|error| exit_with_error(error));
+
+            match expression.permutation_from_assignments(assignments) {
+                Ok(permutation) => println!("{}", expression.evaluate_permutation(permutation)),
+                Err(error) => exit_with_error(error),
+            }
+        }
+        ["--diff", left, right] => {
+            let [left, right] = [left, right].map(|input| {
+                let mut expression = Expression::try_parse_with_options(input, &options)
+                    .unwrap_or_else(|error| exit_with_error(error));
+
+                TruthTable::from_expression(&mut expression)
+            });
+
+            left.print_diff(&right)
+                .unwrap_or_else(|error| exit_with_error(error));
+        }
+        ["--repl"] => repl(&options),
+        ["-c" | "--check", input] => {
+            let errors = Expression::diagnose_with_options(input, &options);
+
+            if errors.is_empty() {
+                println!("OK");
+            } else {
+                // Report every error at once, each underlined in the expression
+                for error in errors {
+                    eprint!("{}", error.annotate(input));
+                }
+
+                std::process::exit(1);
+            }
+        }
+        _ => {
+            println!(
+                "Usage: {} [-e | --expression] [-f | --file] [-t | --truth-table] [-c | --check] <input> [--vars <count> | --row <index> | --verbose | --allow-gaps | --implicit-and | --prefix | --postfix | --only-true | --only-false]",
+                args[0]
+            );
+            println!("       {} [-m | --minterms] <count> <minterms>", args[0]);
+            println!("       {} --eval <expression> <assignments>", args[0]);
+            println!("       {} --diff <expression> <expression>", args[0]);
+            println!("       {} --repl", args[0]);
+            println!("Add --syntax <standard | c-style | math | verilog | python | words | latex> to choose the operator spellings");
+            println!("for both input and output, where ascii and unicode also select standard and math, or");
+            println!("set a default with a 'syntax = <profile>' line in ~/.truth_tables");
+            println!("Extra operator spellings can be added there with 'alias = <alias> <spelling>' lines, such as 'alias = . &'");
+            println!(
+                "Add --max-depth <depth> to change how deeply parentheses can be nested (default {}, at most {})",
+                ParseOptions::default().max_depth,
+                Expression::MAX_NESTING_DEPTH
+            );
+            println!("Use -f <path> in place of -e to read the expression from a file, or give - to -e to read it from stdin");
+            println!("Add --timing to any expression command to report how long each step took");
+            println!("Note: The flag you choose determines the input type, not the output type");
+            std::process::exit(1);
+        }
     }
 }
+
+// Represents what an input is treated as, either by the -e and -t flags or by the mode of the REPL
+#[derive(Clone, Copy)]
+enum InputMode {
+    Expression,
+    TruthTable,
+}
+
+// Prints the truth table of an expression or the disjunction of truth table rows, returning an error if the input is
+// invalid so that the REPL can continue after it
+fn run_input(
+    mode: InputMode,
+    input: &str,
+    options: &ParseOptions,
+    timing: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match mode {
+        InputMode::Expression => {
+            let mut expression = timed(timing, "Parsing", || {
+                Expression::try_parse_with_options(input, options)
+            })?;
+            let table = timed(timing, "Tabulation", || {
+                TruthTable::from_expression(&mut expression)
+            });
+
+            timed(timing, "Output", || table.print());
+        }
+        InputMode::TruthTable => {
+            print_disjunction(&TruthTable::try_parse_rows(input)?, options.syntax)
+        }
+    }
+
+    Ok(())
+}
+
+// Reads lines from stdin until EOF or "quit", printing the result of each line without exiting on errors
+// The mode can be switched with ":mode expr" or ":mode table"
+fn repl(options: &ParseOptions) {
+    let interactive = std::io::stdin().is_terminal();
+    let mut mode = InputMode::Expression;
+    let mut lines = std::io::stdin().lock().lines();
+
+    loop {
+        if interactive {
+            print!("> ");
+            std::io::stdout().flush().unwrap();
+        }
+
+        let line = match lines.next() {
+            Some(line) => line.unwrap_or_else(|error| exit_with_error(error)),
+            None => break,
+        };
+
+        match line.trim() {
+            "" => (),
+            input if input.starts_with('#') => (),
+            "quit" => break,
+            ":mode expr" => mode = InputMode::Expression,
+            ":mode table" => mode = InputMode::TruthTable,
+            input if input.starts_with(":mode") => {
+                eprintln!("Error: Unknown mode, expected ':mode expr' or ':mode table'")
+            }
+            input => {
+                if let Err(error) = run_input(mode, input, options, false) {
+                    eprintln!("Error: {}", error);
+                }
+            }
+        }
+    }
+}
+
+// Removes a flag and the value after it from the arguments, returning the value if the flag was given
+fn take_flag_value(args: &mut Vec<String>, flag: &str) -> Option<String> {
+    match args.iter().position(|arg| arg == flag) {
+        Some(i) if i + 1 < args.len() => {
+            let value = args.remove(i + 1);
+            args.remove(i);
+
+            Some(value)
+        }
+        Some(_) => exit_with_error(format!("Missing value after {}", flag)),
+        None => None,
+    }
+}
+
+// Removes "--syntax <profile>" from the arguments and returns the profile, or the one set in the config file if the
+// flag is not given
+fn take_syntax(args: &mut Vec<String>) -> SyntaxProfile {
+    let name = take_flag_value(args, "--syntax").or_else(|| read_config("syntax").pop());
+
+    name.map_or(SyntaxProfile::Standard, |name| {
+        name.parse().unwrap_or_else(|error| exit_with_error(error))
+    })
+}
+
+// Reads the operator aliases set in the config file, each on an "alias = <alias> <spelling>" line such as "alias = . &"
+fn read_aliases() -> OperatorAliases {
+    let mut aliases = OperatorAliases::new();
+
+    for value in read_config("alias") {
+        match value.split_whitespace().collect::<Vec<&str>>()[..] {
+            [alias, spelling] => aliases
+                .add(alias, spelling)
+                .unwrap_or_else(|error| exit_with_error(error)),
+            _ => exit_with_error(format!(
+                "Invalid alias '{}', expected an alias and a spelling such as '. &'",
+                value
+            )),
+        }
+    }
+
+    aliases
+}
+
+// Reads every value of a setting from the config file at ~/.truth_tables, which contains "key = value" lines
+// Comments run from '#' to the end of the line, including after a setting
+fn read_config(key: &str) -> Vec<String> {
+    let config = std::env::var_os("HOME")
+        .and_then(|home| std::fs::read_to_string(Path::new(&home).join(".truth_tables")).ok())
+        .unwrap_or_default();
+
+    config
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter_map(|line| {
+            let (name, value) = line.split_once('=')?;
+
+            (name.trim() == key).then(|| value.trim().to_string())
+        })
+        .collect()
+}
+
+// Prints the disjunction of a truth table using the spellings of the syntax profile
+fn print_disjunction(table: &TruthTable, syntax: SyntaxProfile) {
+    let disjunction = table.to_disjunction();
+
+    // A contradiction has no terms, so its empty disjunction cannot be converted
+    match Expression::try_parse(&disjunction, false) {
+        Ok(expression) if syntax != SyntaxProfile::Standard => {
+            println!("{}", expression.to_string_with_syntax(syntax))
+        }
+        _ => println!("{}", disjunction),
+    }
+}
+
+// Reads an expression from stdin, so that it can span multiple lines
+fn read_stdin() -> String {
+    let mut expression = String::new();
+
+    std::io::stdin()
+        .read_to_string(&mut expression)
+        .unwrap_or_else(|error| exit_with_error(error));
+
+    expression
+}
+
+// Reads an expression from the file at the given path, so that it can span multiple lines
+fn read_file(path: &str) -> String {
+    std::fs::read_to_string(path)
+        .unwrap_or_else(|error| exit_with_error(format!("Could not read '{}': {}", path, error)))
+}
+
+// Parses an expression with the given parser and builds its truth table, exiting if the expression is invalid
+fn tabulate(timing: bool, parse: impl FnOnce() -> Result<Expression, ParseError>) -> TruthTable {
+    tabulate_with(timing, parse, TruthTable::from_expression)
+}
+
+// Parses an expression with the given parser and builds its truth table with the given function, exiting if the
+// expression is invalid
+fn tabulate_with(
+    timing: bool,
+    parse: impl FnOnce() -> Result<Expression, ParseError>,
+    build: impl FnOnce(&mut Expression) -> TruthTable,
+) -> TruthTable {
+    let mut expression = timed(timing, "Parsing", || {
+        parse().unwrap_or_else(|error| exit_with_error(error))
+    });
+
+    timed(timing, "Tabulation", || build(&mut expression))
+}
+
+// Runs a step of the program, printing how long it took to stderr if timing is enabled
+fn timed<T>(timing: bool, step: &str, f: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = f();
+
+    if timing {
+        eprintln!("{}: {:?}", step, start.elapsed());
+    }
+
+    result
+}
+
+// Prints an error message and exits with a failure status
+fn exit_with_error(error: impl Display) -> ! {
+    eprintln!("Error: {}", error);
+    std::process::exit(1);
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeSet;
+
+// Represents a product term over the conventional row index bits, where the first proposition is the most significant bit
+// Bits set in the mask are eliminated from the term, and the remaining bits of the value are its literals
+#[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct Implicant {
+    pub value: u32,
+    pub mask: u32,
+}
+
+impl Implicant {
+    fn new(value: u32, mask: u32) -> Self {
+        Self { value, mask }
+    }
+
+    // Checks whether the implicant is true for a given row index
+    pub fn covers(&self, index: u32) -> bool {
+        index & !self.mask == self.value
+    }
+
+    // Returns the number of literals in the implicant for a given number of propositions
+    pub fn literal_count(&self, proposition_count: u8) -> u32 {
+        proposition_count as u32 - self.mask.count_ones()
+    }
+
+    // Combines two implicants which differ in exactly one literal, eliminating that literal
+    fn combine(&self, other: &Implicant) -> Option<Implicant> {
+        let difference = self.value ^ other.value;
+
+        if self.mask == other.mask && difference.count_ones() == 1 {
+            Some(Self::new(self.value & !difference, self.mask | difference))
+        } else {
+            None
+        }
+    }
+}
+
+// Finds a minimal set of implicants which covers all minterms without covering any other rows except don't-cares
+// Prime implicants are found with the Quine-McCluskey method, then Petrick's method selects the cover
+// with the fewest terms, breaking ties by the fewest literals
+pub fn minimize(minterms: &[u32], dont_cares: &[u32], proposition_count: u8) -> Vec<Implicant> {
+    if minterms.is_empty() {
+        return Vec::new();
+    }
+
+    let primes = prime_implicants(minterms, dont_cares);
+
+    // Petrick's method: each minterm must be covered by at least one of the prime implicants covering it,
+    // so the product of those sums is expanded into a sum of products, where each product is a candidate cover
+    let mut covers: Vec<BTreeSet<usize>> = vec![BTreeSet::new()];
+
+    for minterm in minterms {
+        let covering: Vec<usize> = (0..primes.len())
+            .filter(|i| primes[*i].covers(*minterm))
+            .collect();
+
+        let mut expanded: Vec<BTreeSet<usize>> = Vec::new();
+
+        for cover in &covers {
+            for prime in &covering {
+                let mut candidate = cover.clone();
+                candidate.insert(*prime);
+
+                expanded.push(candidate);
+            }
+        }
+
+        covers = absorb(expanded);
+    }
+
+    // Choose the cover with the fewest terms, then the fewest literals
+    let best = covers
+        .into_iter()
+        .min_by_key(|cover| {
+            let literals: u32 = cover
+                .iter()
+                .map(|i| primes[*i].literal_count(proposition_count))
+                .sum();
+
+            (cover.len(), literals)
+        })
+        .unwrap();
+
+    best.into_iter().map(|i| primes[i]).collect()
+}
+
+// Finds all prime implicants of a function by repeatedly combining implicants which differ in one literal
+fn prime_implicants(minterms: &[u32], dont_cares: &[u32]) -> Vec<Implicant> {
+    let mut implicants: BTreeSet<Implicant> = minterms
+        .iter()
+        .chain(dont_cares)
+        .map(|index| Implicant::new(*index, 0))
+        .collect();
+
+    let mut primes = BTreeSet::new();
+
+    while !implicants.is_empty() {
+        let mut combined = BTreeSet::new();
+        let mut used = BTreeSet::new();
+
+        for a in &implicants {
+            for b in &implicants {
+                if let Some(implicant) = a.combine(b) {
+                    combined.insert(implicant);
+                    used.insert(*a);
+                    used.insert(*b);
+                }
+            }
+        }
+
+        // Any implicant which could not be combined further is prime
+        primes.extend(implicants.difference(&used).copied());
+        implicants = combined;
+    }
+
+    primes.into_iter().collect()
+}
+
+// Removes any cover which is a superset of another cover, since it can never be smaller
+fn absorb(covers: Vec<BTreeSet<usize>>) -> Vec<BTreeSet<usize>> {
+    let mut absorbed: Vec<BTreeSet<usize>> = Vec::new();
+
+    for cover in covers {
+        if absorbed.iter().any(|other| other.is_subset(&cover)) {
+            continue;
+        }
+
+        absorbed.retain(|other| !cover.is_subset(other));
+        absorbed.push(cover);
+    }
+
+    absorbed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prime_implicants() {
+        // f(A,B) = Σm(2,3) has the single prime implicant A
+        assert_eq!(prime_implicants(&[2, 3], &[]), vec![Implicant::new(2, 1)]);
+
+        // f(A,B,C) = Σm(0,1,2,5,6,7) has six prime implicants of two literals each
+        let primes = prime_implicants(&[0, 1, 2, 5, 6, 7], &[]);
+        assert_eq!(primes.len(), 6);
+        assert!(primes.iter().all(|prime| prime.literal_count(3) == 2));
+    }
+
+    #[test]
+    fn test_minimize_cyclic() {
+        // f(A,B,C) = Σm(0,1,2,5,6,7) has no essential prime implicants, and needs three of its six primes
+        let minterms = [0, 1, 2, 5, 6, 7];
+        let cover = minimize(&minterms, &[], 3);
+
+        assert_eq!(cover.len(), 3);
+        assert_eq!(cover.iter().map(|i| i.literal_count(3)).sum::<u32>(), 6);
+
+        for index in 0..8 {
+            assert_eq!(
+                cover.iter().any(|implicant| implicant.covers(index)),
+                minterms.contains(&index)
+            );
+        }
+    }
+}
@@ -1,83 +1,141 @@
-use std::collections::HashMap;
-
-// Represents one of the allowed root proposition letters ("identifiers")
-#[derive(Eq, PartialEq, Hash, Debug)]
-pub enum PropositionIdentifier {
-    A,
-    B,
-    C,
-    D,
-}
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+// The single-letter proposition names
+const LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+// Represents a root proposition ("identifier"), stored as its name
+// The name is shared between clones and freed with the last of them, so parsing new names never leaks memory
+// Identifiers are ordered by their names, which is the order of their columns in a truth table
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Debug)]
+pub struct PropositionIdentifier(Arc<str>);
 
 // Stores a table of all the proposition identifiers, and their respective values
-#[derive(Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct PropositionTable {
-    propositions: HashMap<PropositionIdentifier, Option<bool>>,
+    propositions: BTreeMap<PropositionIdentifier, Option<bool>>,
 }
 
 impl PropositionIdentifier {
-    // Returns the masked value of the proposition for a given permutation of propositions, in 0bABCD format
-    pub fn mask(&self, permutation: u8) -> bool {
-        match self {
-            Self::A => permutation & 0b1000 != 0,
-            Self::B => permutation & 0b0100 != 0,
-            Self::C => permutation & 0b0010 != 0,
-            Self::D => permutation & 0b0001 != 0,
-        }
-    }
+    // The maximum number of distinct propositions in a truth table
+    pub const MAX_COUNT: u8 = 26;
 
     // Converts a char to a PropositionIdentifier
     pub fn from_char(c: char) -> Self {
-        match c {
-            'a' | 'A' => Self::A,
-            'b' | 'B' => Self::B,
-            'c' | 'C' => Self::C,
-            'd' | 'D' => Self::D,
-            _ => unreachable!("[INTERNAL ERROR] Invalid proposition character '{}'", c),
+        Self::try_from_char(c).unwrap_or_else(|| {
+            unreachable!("[INTERNAL ERROR] Invalid proposition character '{}'", c)
+        })
+    }
+
+    // Converts a char to a PropositionIdentifier, returning None if it is not a letter from A to Z
+    // Letters are folded to uppercase, so "a" and "A" are the same proposition
+    pub fn try_from_char(c: char) -> Option<Self> {
+        match c.to_ascii_uppercase() {
+            c @ 'A'..='Z' => Some(Self::from_int(c as u8 - b'A')),
+            _ => None,
         }
     }
 
-    // Converts a u8 to a PropositionIdentifier
+    // Converts a u8 to the PropositionIdentifier of the letter at that index in the alphabet
     pub fn from_int(i: u8) -> Self {
-        match i {
-            0 => Self::A,
-            1 => Self::B,
-            2 => Self::C,
-            3 => Self::D,
-            _ => unreachable!("[INTERNAL ERROR] Invalid proposition integer '{}'", i),
+        if i >= Self::MAX_COUNT {
+            unreachable!("[INTERNAL ERROR] Invalid proposition integer '{}'", i);
+        }
+
+        let i = i as usize;
+
+        Self(Arc::from(&LETTERS[i..i + 1]))
+    }
+
+    // Converts a name such as "A", "rain" or "is_admin" to a PropositionIdentifier
+    pub fn from_name(name: &str) -> Self {
+        Self::try_from_name(name)
+            .unwrap_or_else(|| unreachable!("[INTERNAL ERROR] Invalid proposition name '{}'", name))
+    }
+
+    // Converts a name to a PropositionIdentifier, returning None if it is not a letter or underscore followed by any
+    // letters, digits and underscores
+    // Single letters are folded to uppercase, but longer names keep their case
+    pub fn try_from_name(name: &str) -> Option<Self> {
+        let mut chars = name.chars();
+
+        match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => Self::try_from_char(c),
+            _ => Self::try_from_name_case_sensitive(name),
         }
     }
 
-    // Converts a PropositionIdentifier to a char
-    pub fn to_char(&self) -> char {
-        match self {
-            Self::A => 'A',
-            Self::B => 'B',
-            Self::C => 'C',
-            Self::D => 'D',
+    // Converts a name to a PropositionIdentifier like try_from_name, but without folding single letters, so "a" and
+    // "A" are different propositions
+    pub fn try_from_name_case_sensitive(name: &str) -> Option<Self> {
+        match name.chars().next() {
+            Some(c @ 'A'..='Z') if name.len() == 1 => Self::try_from_char(c),
+            Some(c) if is_name_start(c) && name.chars().all(is_name_char) => {
+                Some(Self(Arc::from(name)))
+            }
+            _ => None,
+        }
+    }
+
+    // Returns the name of the proposition, which is how it is shown in expressions and table headers
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+
+    // Returns the index of the proposition in the alphabet starting from the given letter, or None if its name is not
+    // a single letter of the same case
+    fn letter_index(&self, first: u8) -> Option<u8> {
+        match self.0.as_bytes() {
+            [c] if (first..first + Self::MAX_COUNT).contains(c) => Some(c - first),
+            _ => None,
         }
     }
 }
 
+impl fmt::Display for PropositionIdentifier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+// Parses a proposition name such as "A" or "is_admin" into a PropositionIdentifier
+impl FromStr for PropositionIdentifier {
+    type Err = InvalidProposition;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::try_from_name(s).ok_or_else(|| InvalidProposition(s.to_string()))
+    }
+}
+
 impl PropositionTable {
-    fn new(propositions: HashMap<PropositionIdentifier, Option<bool>>) -> Self {
+    fn new(propositions: BTreeMap<PropositionIdentifier, Option<bool>>) -> Self {
         Self { propositions }
     }
 
-    // Parses a string into a PropositionTable
+    // Parses a string into a PropositionTable, where each word of letters, digits and underscores is a proposition
     pub fn from_expression_str(expression: &str) -> Self {
-        let mut propositions: HashMap<PropositionIdentifier, Option<bool>> = HashMap::new();
-
-        for c in expression.chars() {
-            match c {
-                'A'..='D' | 'a'..='d' => {
-                    propositions.insert(PropositionIdentifier::from_char(c), None);
-                }
-                _ => (),
-            }
-        }
+        let identifiers = expression
+            .split(|c: char| !is_name_char(c))
+            .filter_map(PropositionIdentifier::try_from_name);
 
-        Self::new(propositions)
+        Self::from_identifiers(identifiers)
+    }
+
+    // Creates a PropositionTable containing the given proposition identifiers
+    pub fn from_identifiers(identifiers: impl IntoIterator<Item = PropositionIdentifier>) -> Self {
+        Self::new(
+            identifiers
+                .into_iter()
+                .map(|identifier| (identifier, None))
+                .collect(),
+        )
+    }
+
+    // Returns the identifiers of all propositions in the table, ordered by their names
+    pub fn identifiers(&self) -> Vec<PropositionIdentifier> {
+        self.propositions.keys().cloned().collect()
     }
 
     // Returns the value of a proposition in the table
@@ -85,42 +143,78 @@ impl PropositionTable {
         self.propositions.get(identifier).copied().flatten()
     }
 
-    // Sets the true/false values of all the propositions in the table by bitmasking a provided u8 (0b0000DCBA)
-    pub fn set_all(&mut self, values: u8) {
+    // Sets the true/false values of all the propositions in the table by bitmasking a provided permutation (0bABC...Z)
+    // Each proposition takes the bit of its position in the table, so the first name is the most significant bit
+    pub fn set_all(&mut self, values: u32) {
+        for (position, value) in self.propositions.values_mut().enumerate() {
+            *value = Some(values & proposition_bit(position) != 0);
+        }
+    }
+
+    // Sets the values of all the propositions in the table to their values in another table which contains them
+    pub(crate) fn set_from(&mut self, other: &PropositionTable) {
         for (proposition, value) in self.propositions.iter_mut() {
-            *value = Some(proposition.mask(values));
+            *value = other.get_value(proposition);
         }
     }
 
     // Returns the number of propositions in the table
+    // Tables can be built from any number of names, so the count saturates instead of wrapping around
     pub fn count(&self) -> u8 {
-        self.propositions.len() as u8
+        u8::try_from(self.propositions.len()).unwrap_or(u8::MAX)
     }
 
-    // Ensures that there are no skipped identifiers
-    // This is a really ugly way to do this and it's not very scalable, but it should do fine for this assignment
+    // Ensures that there are no skipped letters, such as using A and C but not B
+    // Lowercase letters are only kept apart from uppercase ones in case-sensitive mode, so each case is checked on its
+    // own, and propositions with longer names are not lettered, so they are not checked
+    // A table without any propositions is valid, since expressions can consist only of constants
     pub fn validate(&self) -> bool {
-        use PropositionIdentifier::*;
-
-        match self.propositions.len() {
-            1 => self.propositions.contains_key(&A),
-            2 => self.propositions.contains_key(&A) && self.propositions.contains_key(&B),
-            3 => {
-                self.propositions.contains_key(&A)
-                    && self.propositions.contains_key(&B)
-                    && self.propositions.contains_key(&C)
-            }
-            4 => {
-                self.propositions.contains_key(&A)
-                    && self.propositions.contains_key(&B)
-                    && self.propositions.contains_key(&C)
-                    && self.propositions.contains_key(&D)
-            }
-            _ => false,
-        }
+        [b'A', b'a'].into_iter().all(|first| {
+            let letters: Vec<u8> = self
+                .propositions
+                .keys()
+                .filter_map(|identifier| identifier.letter_index(first))
+                .collect();
+
+            letters
+                .iter()
+                .all(|index| (*index as usize) < letters.len())
+        })
+    }
+}
+
+// Returns the bit which represents the proposition at the given position in a permutation, in 0bABC...Z format
+// The first proposition is always the most significant bit of the u32, so permutations sort in row order
+pub(crate) fn proposition_bit(position: usize) -> u32 {
+    1 << (31 - position)
+}
+
+// Checks whether a character can start a proposition name
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+// Checks whether a character can be part of a proposition name
+pub(crate) fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+// Represents a string which could not be parsed into a PropositionIdentifier
+#[derive(PartialEq, Debug)]
+pub struct InvalidProposition(String);
+
+impl fmt::Display for InvalidProposition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Invalid proposition '{}', expected a letter or underscore followed by letters, digits or underscores",
+            self.0
+        )
     }
 }
 
+impl std::error::Error for InvalidProposition {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,27 +245,176 @@ mod tests {
         let expression = "A & B & C & D";
         let mut table = PropositionTable::from_expression_str(expression);
 
-        use PropositionIdentifier::*;
+        let [a, b, c, d] = ['A', 'B', 'C', 'D'].map(PropositionIdentifier::from_char);
 
+        // Permutations are left-aligned in a u32, so A is the highest bit
         table.set_all(0b0000);
 
-        assert_eq!(table.get_value(&A), Some(false));
-        assert_eq!(table.get_value(&B), Some(false));
-        assert_eq!(table.get_value(&C), Some(false));
-        assert_eq!(table.get_value(&D), Some(false));
+        assert_eq!(table.get_value(&a), Some(false));
+        assert_eq!(table.get_value(&b), Some(false));
+        assert_eq!(table.get_value(&c), Some(false));
+        assert_eq!(table.get_value(&d), Some(false));
+
+        table.set_all(0b1111 << 28);
+
+        assert_eq!(table.get_value(&a), Some(true));
+        assert_eq!(table.get_value(&b), Some(true));
+        assert_eq!(table.get_value(&c), Some(true));
+        assert_eq!(table.get_value(&d), Some(true));
+
+        table.set_all(0b0101 << 28);
+
+        assert_eq!(table.get_value(&a), Some(false));
+        assert_eq!(table.get_value(&b), Some(true));
+        assert_eq!(table.get_value(&c), Some(false));
+        assert_eq!(table.get_value(&d), Some(true));
+    }
+
+    #[test]
+    fn test_identifiers_beyond_d() {
+        let table = PropositionTable::from_expression_str("A & B & C & D & E & F & G & H");
+        assert!(table.validate());
+        assert_eq!(table.count(), 8);
+
+        let h = PropositionIdentifier::from_char('h');
+        assert_eq!(h.name(), "H");
+        assert_eq!(h, PropositionIdentifier::from_int(7));
 
-        table.set_all(0b1111);
+        let mut table = table;
+        table.set_all(1 << 24);
+        assert_eq!(table.get_value(&h), Some(true));
+        table.set_all(1 << 25);
+        assert_eq!(table.get_value(&h), Some(false));
+
+        assert_eq!(PropositionIdentifier::from_int(25).name(), "Z");
+        assert!(!PropositionTable::from_expression_str("A & B & Z").validate());
+    }
+
+    #[test]
+    fn test_try_from_char() {
+        assert_eq!(
+            PropositionIdentifier::try_from_char('A'),
+            Some(PropositionIdentifier::from_int(0))
+        );
+        assert_eq!(
+            PropositionIdentifier::try_from_char('d'),
+            Some(PropositionIdentifier::from_int(3))
+        );
+        // Identifiers extend past D to Z
+        assert_eq!(
+            PropositionIdentifier::try_from_char('E'),
+            Some(PropositionIdentifier::from_int(4))
+        );
+        assert_eq!(PropositionIdentifier::try_from_char('1'), None);
+        assert_eq!(PropositionIdentifier::try_from_char('_'), None);
+        assert_eq!(PropositionIdentifier::try_from_char('é'), None);
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("A".parse(), Ok(PropositionIdentifier::from_char('A')));
+        assert_eq!("d".parse(), Ok(PropositionIdentifier::from_char('D')));
+        assert_eq!(
+            "1".parse::<PropositionIdentifier>(),
+            Err(InvalidProposition(String::from("1")))
+        );
+        assert_eq!(
+            "1a".parse::<PropositionIdentifier>(),
+            Err(InvalidProposition(String::from("1a")))
+        );
+        assert_eq!(
+            "is-admin".parse::<PropositionIdentifier>(),
+            Err(InvalidProposition(String::from("is-admin")))
+        );
+        assert_eq!(
+            "".parse::<PropositionIdentifier>(),
+            Err(InvalidProposition(String::new()))
+        );
+        assert_eq!(
+            "1".parse::<PropositionIdentifier>()
+                .unwrap_err()
+                .to_string(),
+            "Invalid proposition '1', expected a letter or underscore followed by letters, digits or underscores"
+        );
+    }
+
+    #[test]
+    fn test_names() {
+        let [rain, p1, is_admin] = ["rain", "p1", "is_admin"].map(PropositionIdentifier::from_name);
+
+        assert_eq!(rain.name(), "rain");
+        assert_eq!(is_admin.to_string(), "is_admin");
+        assert_eq!("p1".parse(), Ok(p1.clone()));
+        assert_eq!(
+            "_".parse::<PropositionIdentifier>().map(|p| p.to_string()),
+            Ok(String::from("_"))
+        );
+
+        // Parsing a name again gives an equal identifier
+        assert_eq!(PropositionIdentifier::from_name("rain"), rain);
+
+        // Clones share the name, which is freed once the last of them is dropped
+        let copy = rain.clone();
+        assert!(std::ptr::eq(copy.name(), rain.name()));
+        assert_eq!(Arc::strong_count(&rain.0), 2);
+        drop(copy);
+        assert_eq!(Arc::strong_count(&rain.0), 1);
+
+        // Single letters are folded to uppercase, but longer names keep their case
+        assert_eq!(
+            PropositionIdentifier::from_name("a"),
+            PropositionIdentifier::from_char('A')
+        );
+        assert_ne!(PropositionIdentifier::from_name("Rain"), rain);
+
+        // Propositions are ordered by their names
+        let table = PropositionTable::from_expression_str("rain & B & p1 & is_admin & A");
+        assert_eq!(
+            table.identifiers(),
+            vec![
+                PropositionIdentifier::from_char('A'),
+                PropositionIdentifier::from_char('B'),
+                is_admin,
+                p1,
+                rain
+            ]
+        );
+
+        // Only single letters need to be consecutive
+        assert!(table.validate());
+        assert!(PropositionTable::from_expression_str("rain & p1").validate());
+        assert!(!PropositionTable::from_expression_str("rain & B").validate());
+    }
+
+    #[test]
+    fn test_case_sensitive_names() {
+        let lower = PropositionIdentifier::try_from_name_case_sensitive("a").unwrap();
+        let upper = PropositionIdentifier::try_from_name_case_sensitive("A").unwrap();
 
-        assert_eq!(table.get_value(&A), Some(true));
-        assert_eq!(table.get_value(&B), Some(true));
-        assert_eq!(table.get_value(&C), Some(true));
-        assert_eq!(table.get_value(&D), Some(true));
+        assert_ne!(lower, upper);
+        assert_eq!(lower.name(), "a");
+        assert_eq!(upper, PropositionIdentifier::from_char('a'));
+        assert_eq!(
+            PropositionIdentifier::try_from_name_case_sensitive("rain"),
+            Some(PropositionIdentifier::from_name("rain"))
+        );
+        assert_eq!(
+            PropositionIdentifier::try_from_name_case_sensitive("1"),
+            None
+        );
 
-        table.set_all(0b0101);
+        // Each case of letters must be consecutive on its own
+        let table = |names: &[&str]| {
+            PropositionTable::from_identifiers(
+                names
+                    .iter()
+                    .map(|name| PropositionIdentifier::try_from_name_case_sensitive(name).unwrap()),
+            )
+        };
 
-        assert_eq!(table.get_value(&A), Some(false));
-        assert_eq!(table.get_value(&B), Some(true));
-        assert_eq!(table.get_value(&C), Some(false));
-        assert_eq!(table.get_value(&D), Some(true));
+        assert!(table(&["a", "A", "b"]).validate());
+        assert!(table(&["a", "b", "A", "B"]).validate());
+        assert!(!table(&["a", "c", "A"]).validate());
+        assert!(!table(&["a", "B"]).validate());
     }
 }
@@ -0,0 +1,444 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::expressions::Operator;
+use crate::lexer::TokenKind;
+
+// Represents a set of operator spellings, which controls what the parser accepts and what the printer emits
+// The standard profile accepts every symbol, keyword and LaTeX command spelling, while the others only accept their own
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum SyntaxProfile {
+    // "&", "|" and "!", such as "!A & (B | C)"
+    #[default]
+    Standard,
+    // "&&", "||" and "!", such as "!A && (B || C)"
+    CStyle,
+    // "∧", "∨" and "¬", such as "¬A ∧ (B ∨ C)"
+    Math,
+    // "&", "|" and "~", such as "~A & (B | C)"
+    Verilog,
+    // "and", "or" and "not", such as "not A and (B or C)"
+    Python,
+    // "AND", "OR" and "NOT", such as "NOT A AND (B OR C)"
+    Words,
+    // "\land", "\lor" and "\neg", such as "\neg A \land (B \lor C)", for pasting into LaTeX documents
+    Latex,
+}
+
+use Operator::*;
+use TokenKind::Negation;
+use TokenKind::Operator as Op;
+
+// The spellings of each operator in the order they are printed, followed by any other accepted spellings
+// Spellings are matched longest first, so "!&" is read as NAND rather than a negation followed by AND
+const STANDARD_SYMBOLS: &[(&str, TokenKind)] = &[
+    ("&", Op(And)),
+    ("|", Op(Or)),
+    ("!&", Op(Nand)),
+    ("!|", Op(Nor)),
+    ("^", Op(Xor)),
+    ("~^", Op(Xnor)),
+    ("->", Op(Implies)),
+    ("<->", Op(Iff)),
+    ("!", Negation),
+    ("*", Op(And)),
+    ("+", Op(Or)),
+    ("=>", Op(Implies)),
+    ("<=>", Op(Iff)),
+    ("/", Negation),
+];
+
+const C_STYLE_SYMBOLS: &[(&str, TokenKind)] = &[
+    ("&&", Op(And)),
+    ("||", Op(Or)),
+    ("!&", Op(Nand)),
+    ("!|", Op(Nor)),
+    ("^", Op(Xor)),
+    ("~^", Op(Xnor)),
+    ("->", Op(Implies)),
+    ("<->", Op(Iff)),
+    ("!", Negation),
+];
+
+const MATH_SYMBOLS: &[(&str, TokenKind)] = &[
+    ("∧", Op(And)),
+    ("∨", Op(Or)),
+    ("⊼", Op(Nand)),
+    ("⊽", Op(Nor)),
+    ("⊕", Op(Xor)),
+    ("⊙", Op(Xnor)),
+    ("→", Op(Implies)),
+    ("↔", Op(Iff)),
+    ("¬", Negation),
+];
+
+const VERILOG_SYMBOLS: &[(&str, TokenKind)] = &[
+    ("&", Op(And)),
+    ("|", Op(Or)),
+    ("~&", Op(Nand)),
+    ("~|", Op(Nor)),
+    ("^", Op(Xor)),
+    ("~^", Op(Xnor)),
+    ("->", Op(Implies)),
+    ("<->", Op(Iff)),
+    ("~", Negation),
+];
+
+const STANDARD_KEYWORDS: &[(&str, TokenKind)] = &[
+    ("AND", Op(And)),
+    ("OR", Op(Or)),
+    ("NAND", Op(Nand)),
+    ("NOR", Op(Nor)),
+    ("XOR", Op(Xor)),
+    ("NOT", Negation),
+];
+
+const WORD_KEYWORDS: &[(&str, TokenKind)] = &[
+    ("AND", Op(And)),
+    ("OR", Op(Or)),
+    ("NAND", Op(Nand)),
+    ("NOR", Op(Nor)),
+    ("XOR", Op(Xor)),
+    ("XNOR", Op(Xnor)),
+    ("IMPLIES", Op(Implies)),
+    ("IFF", Op(Iff)),
+    ("NOT", Negation),
+];
+
+const PYTHON_KEYWORDS: &[(&str, TokenKind)] = &[
+    ("and", Op(And)),
+    ("or", Op(Or)),
+    ("nand", Op(Nand)),
+    ("nor", Op(Nor)),
+    ("xor", Op(Xor)),
+    ("xnor", Op(Xnor)),
+    ("implies", Op(Implies)),
+    ("iff", Op(Iff)),
+    ("not", Negation),
+];
+
+// LaTeX commands are read as a whole word after the backslash, so they are kept apart from the symbols
+// NAND and NOR are printed as the Sheffer stroke and Peirce arrow, since LaTeX has no commands named after them
+const LATEX_COMMANDS: &[(&str, TokenKind)] = &[
+    ("\\land", Op(And)),
+    ("\\lor", Op(Or)),
+    ("\\uparrow", Op(Nand)),
+    ("\\downarrow", Op(Nor)),
+    ("\\oplus", Op(Xor)),
+    ("\\odot", Op(Xnor)),
+    ("\\rightarrow", Op(Implies)),
+    ("\\leftrightarrow", Op(Iff)),
+    ("\\neg", Negation),
+    ("\\wedge", Op(And)),
+    ("\\vee", Op(Or)),
+    ("\\to", Op(Implies)),
+    ("\\implies", Op(Implies)),
+    ("\\iff", Op(Iff)),
+    ("\\equiv", Op(Iff)),
+    ("\\lnot", Negation),
+];
+
+impl SyntaxProfile {
+    // Returns the symbols accepted by the profile, along with the tokens they represent
+    pub(crate) fn symbols(&self) -> &'static [(&'static str, TokenKind)] {
+        match self {
+            SyntaxProfile::Standard => STANDARD_SYMBOLS,
+            SyntaxProfile::CStyle => C_STYLE_SYMBOLS,
+            SyntaxProfile::Math => MATH_SYMBOLS,
+            SyntaxProfile::Verilog => VERILOG_SYMBOLS,
+            SyntaxProfile::Python | SyntaxProfile::Words | SyntaxProfile::Latex => &[],
+        }
+    }
+
+    // Returns the keywords accepted by the profile, which are matched ignoring case
+    fn keywords(&self) -> &'static [(&'static str, TokenKind)] {
+        match self {
+            SyntaxProfile::Standard => STANDARD_KEYWORDS,
+            SyntaxProfile::Python => PYTHON_KEYWORDS,
+            SyntaxProfile::Words => WORD_KEYWORDS,
+            _ => &[],
+        }
+    }
+
+    // Returns the token written as the given keyword, ignoring case
+    pub(crate) fn keyword(&self, word: &str) -> Option<TokenKind> {
+        self.keywords()
+            .iter()
+            .find(|(keyword, _)| keyword.eq_ignore_ascii_case(word))
+            .map(|(_, kind)| kind.clone())
+    }
+
+    // Returns the longest symbol accepted by the profile at the start of the string, along with the token it represents
+    pub(crate) fn symbol_at(&self, expression: &str) -> Option<(&'static str, TokenKind)> {
+        self.symbols()
+            .iter()
+            .filter(|(symbol, _)| expression.starts_with(symbol))
+            .max_by_key(|(symbol, _)| symbol.len())
+            .map(|(symbol, kind)| (*symbol, kind.clone()))
+    }
+
+    // Returns the token written with exactly the given spelling, matching keywords ignoring case
+    fn token(&self, spelling: &str) -> Option<TokenKind> {
+        self.symbols()
+            .iter()
+            .find(|(symbol, _)| *symbol == spelling)
+            .map(|(_, kind)| kind.clone())
+            .or_else(|| self.keyword(spelling))
+    }
+
+    // Returns whether LaTeX commands such as "\land" are accepted
+    pub(crate) fn accepts_latex(&self) -> bool {
+        matches!(
+            self,
+            SyntaxProfile::Standard | SyntaxProfile::Math | SyntaxProfile::Latex
+        )
+    }
+
+    // Returns the token written as the given LaTeX command, without its leading backslash
+    pub(crate) fn latex_command(command: &str) -> Option<TokenKind> {
+        LATEX_COMMANDS
+            .iter()
+            .find(|(spelling, _)| spelling[1..] == *command)
+            .map(|(_, kind)| kind.clone())
+    }
+
+    // Returns the spelling printed for the given token, which is the first one listed for it
+    // LaTeX commands are only printed by the LaTeX profile, since the others list their own spellings first
+    fn spelling(&self, kind: &TokenKind) -> &'static str {
+        let commands = match self {
+            SyntaxProfile::Latex => LATEX_COMMANDS,
+            _ => &[],
+        };
+
+        self.symbols()
+            .iter()
+            .chain(self.keywords())
+            .chain(commands)
+            .find(|(_, other)| other == kind)
+            .map(|(spelling, _)| *spelling)
+            .unwrap_or_else(|| unreachable!("[INTERNAL ERROR] Missing spelling for {:?}", kind))
+    }
+
+    // Returns the spelling printed for the given operator
+    pub(crate) fn operator(&self, operator: &Operator) -> &'static str {
+        self.spelling(&Op(operator.clone()))
+    }
+
+    // Returns whether the operator is printed with its own spelling
+    // Verilog has no implication or biconditional operators, so they are only accepted as input and printed in terms
+    // of the others instead
+    pub(crate) fn prints_operator(&self, operator: &Operator) -> bool {
+        !matches!((self, operator), (SyntaxProfile::Verilog, Implies | Iff))
+    }
+
+    // Returns the prefix printed before a negated element, which is followed by a space when it is a word or a LaTeX
+    // command
+    pub(crate) fn negation(&self) -> String {
+        match self.spelling(&Negation) {
+            word if word
+                .trim_start_matches('\\')
+                .chars()
+                .all(|c| c.is_ascii_alphabetic()) =>
+            {
+                format!("{} ", word)
+            }
+            symbol => String::from(symbol),
+        }
+    }
+
+    // Returns the name used to select the profile, such as "c-style"
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyntaxProfile::Standard => "standard",
+            SyntaxProfile::CStyle => "c-style",
+            SyntaxProfile::Math => "math",
+            SyntaxProfile::Verilog => "verilog",
+            SyntaxProfile::Python => "python",
+            SyntaxProfile::Words => "words",
+            SyntaxProfile::Latex => "latex",
+        }
+    }
+}
+
+// Parses the name of a syntax profile, such as "math"
+// The standard and math profiles can also be selected by their output style, as "ascii" and "unicode"
+impl FromStr for SyntaxProfile {
+    type Err = UnknownSyntaxProfile;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("ascii") {
+            return Ok(SyntaxProfile::Standard);
+        } else if s.eq_ignore_ascii_case("unicode") {
+            return Ok(SyntaxProfile::Math);
+        }
+
+        [
+            SyntaxProfile::Standard,
+            SyntaxProfile::CStyle,
+            SyntaxProfile::Math,
+            SyntaxProfile::Verilog,
+            SyntaxProfile::Python,
+            SyntaxProfile::Words,
+            SyntaxProfile::Latex,
+        ]
+        .into_iter()
+        .find(|profile| profile.name().eq_ignore_ascii_case(s))
+        .ok_or_else(|| UnknownSyntaxProfile(s.to_string()))
+    }
+}
+
+// Represents a string which is not the name of a syntax profile
+#[derive(PartialEq, Debug)]
+pub struct UnknownSyntaxProfile(String);
+
+impl fmt::Display for UnknownSyntaxProfile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Unknown syntax profile '{}', expected standard, c-style, math, verilog, python, words or latex",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownSyntaxProfile {}
+
+// Represents extra spellings of operators and negation for the parser to accept, such as "." for AND
+// Each alias is either a word, which is matched ignoring case like a keyword, or a symbol without letters or digits
+// Symbols are matched before comments, so an alias can even take over '#'
+#[derive(Clone, PartialEq, Default, Debug)]
+pub struct OperatorAliases {
+    aliases: Vec<(String, TokenKind)>,
+}
+
+impl OperatorAliases {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Adds an alias for an operator or negation given in any of its standard spellings, such as "&", "->" or "NOT"
+    // Later aliases replace earlier ones with the same spelling
+    pub fn add(&mut self, alias: &str, spelling: &str) -> Result<(), AliasError> {
+        let is_word = alias.chars().all(|c| c.is_ascii_alphabetic());
+        let is_symbol = alias
+            .chars()
+            .all(|c| !c.is_alphanumeric() && !c.is_whitespace() && !"()_".contains(c));
+
+        if alias.is_empty() || !(is_word || is_symbol) {
+            return Err(AliasError::InvalidAlias(alias.to_string()));
+        }
+
+        let kind = SyntaxProfile::Standard
+            .token(spelling)
+            .ok_or_else(|| AliasError::UnknownSpelling(spelling.to_string()))?;
+
+        self.aliases.retain(|(other, _)| other != alias);
+        self.aliases.push((alias.to_string(), kind));
+
+        Ok(())
+    }
+
+    // Returns the token written as the given word alias, ignoring case
+    pub(crate) fn keyword(&self, word: &str) -> Option<TokenKind> {
+        self.aliases
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(word))
+            .map(|(_, kind)| kind.clone())
+    }
+
+    // Returns the longest symbol alias at the start of the string, as its length in bytes and the token it represents
+    pub(crate) fn symbol_at(&self, expression: &str) -> Option<(usize, TokenKind)> {
+        self.aliases
+            .iter()
+            .filter(|(alias, _)| !alias.chars().all(|c| c.is_ascii_alphabetic()))
+            .filter(|(alias, _)| expression.starts_with(alias.as_str()))
+            .max_by_key(|(alias, _)| alias.len())
+            .map(|(alias, kind)| (alias.len(), kind.clone()))
+    }
+}
+
+// Represents a reason that an operator alias could not be added
+#[derive(PartialEq, Debug)]
+pub enum AliasError {
+    InvalidAlias(String),
+    UnknownSpelling(String),
+}
+
+impl fmt::Display for AliasError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AliasError::InvalidAlias(alias) => write!(
+                f,
+                "Invalid alias '{}', aliases must be a word of letters or a symbol without letters, digits, whitespace or parentheses",
+                alias
+            ),
+            AliasError::UnknownSpelling(spelling) => write!(
+                f,
+                "Unknown operator '{}', aliases must refer to a standard spelling such as '&' or 'NOT'",
+                spelling
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AliasError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_symbol_at() {
+        assert_eq!(
+            SyntaxProfile::Standard.symbol_at("!& B"),
+            Some(("!&", Op(Nand)))
+        );
+        assert_eq!(
+            SyntaxProfile::Standard.symbol_at("!B"),
+            Some(("!", Negation))
+        );
+        assert_eq!(SyntaxProfile::CStyle.symbol_at("& B"), None);
+        assert_eq!(
+            SyntaxProfile::Verilog.symbol_at("~B"),
+            Some(("~", Negation))
+        );
+    }
+
+    #[test]
+    fn test_operator_aliases() {
+        let mut aliases = OperatorAliases::new();
+
+        assert_eq!(aliases.add(".", "&"), Ok(()));
+        assert_eq!(aliases.add("-", "NOT"), Ok(()));
+        assert_eq!(aliases.add("v", "|"), Ok(()));
+
+        assert_eq!(aliases.symbol_at(". B"), Some((1, Op(And))));
+        assert_eq!(aliases.symbol_at("-B"), Some((1, Negation)));
+        assert_eq!(aliases.keyword("V"), Some(Op(Or)));
+        assert_eq!(aliases.symbol_at("v B"), None);
+
+        assert_eq!(
+            aliases.add("a1", "&"),
+            Err(AliasError::InvalidAlias(String::from("a1")))
+        );
+        assert_eq!(
+            aliases.add("( ", "&"),
+            Err(AliasError::InvalidAlias(String::from("( ")))
+        );
+        assert_eq!(
+            aliases.add("%", "&&"),
+            Err(AliasError::UnknownSpelling(String::from("&&")))
+        );
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("C-Style".parse(), Ok(SyntaxProfile::CStyle));
+        assert_eq!("unicode".parse(), Ok(SyntaxProfile::Math));
+        assert_eq!("LaTeX".parse(), Ok(SyntaxProfile::Latex));
+        assert_eq!(
+            "lisp".parse::<SyntaxProfile>(),
+            Err(UnknownSyntaxProfile(String::from("lisp")))
+        );
+    }
+}
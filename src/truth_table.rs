@@ -1,303 +1,2662 @@
 use std::collections::BTreeMap;
+use std::fmt;
 
+use crate::expressions::InvalidSubexpressionPath;
+use crate::minimization::minimize;
+use crate::minimization::Implicant;
+use crate::propositions::proposition_bit;
 use crate::Expression;
 use crate::PropositionIdentifier;
 
 // Represents a truth table for a given expression
-// Proposition value permutations are encoded in u8s
+// Proposition value permutations are encoded in u32s
+#[derive(PartialEq, Debug, Default)]
 pub struct TruthTable {
     propositions: Vec<PropositionIdentifier>,
-    values_and_results: BTreeMap<u8, bool>,
-}
-
-impl Default for TruthTable {
-    fn default() -> Self {
-        Self {
-            propositions: Vec::new(),
-            values_and_results: BTreeMap::new(),
-        }
-    }
+    values_and_results: BTreeMap<u32, bool>,
+    subexpression_columns: Vec<(String, BTreeMap<u32, bool>)>,
 }
 
 impl TruthTable {
-    fn new(
+    pub(crate) fn new(
         propositions: Vec<PropositionIdentifier>,
-        values_and_results: BTreeMap<u8, bool>,
+        values_and_results: BTreeMap<u32, bool>,
     ) -> Self {
         Self {
             propositions,
             values_and_results,
+            subexpression_columns: Vec::new(),
         }
     }
 
-    // Creates a new truth table for a given expression
-    fn from_expression(expression: &mut Expression) -> Self {
-        let proposition_count = expression.proposition_count();
-
-        let propositions = get_propositions(proposition_count);
+    // Creates a new truth table for a given expression, over exactly the propositions which appear in it
+    pub fn from_expression(expression: &mut Expression) -> Self {
+        let propositions = expression.propositions().identifiers();
         let mut values_and_results = BTreeMap::new();
 
         // Generate all possible permutations of the propositions
-        for permutation in get_bit_permutations(proposition_count) {
+        for permutation in get_bit_permutations(propositions.len() as u8) {
             values_and_results.insert(permutation, expression.evaluate_permutation(permutation));
         }
 
         Self::new(propositions, values_and_results)
     }
 
-    // Parses a user-inputted set of rows into a truth table
+    // Parses a user-inputted set of rows into a truth table, panicking if they are invalid
     pub fn parse_rows(rows: &str) -> Self {
-        // Split and validate the user-inputted rows
-        let rows = rows.split(", ").collect::<Vec<&str>>();
+        Self::try_parse_rows(rows).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Parses a user-inputted set of rows into a truth table, returning an error if they are invalid
+    pub fn try_parse_rows(rows: &str) -> Result<Self, RowParseError> {
+        // Split the user-inputted rows
+        let rows = split_rows(rows);
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+
+        if rows.is_empty() {
+            return Err(RowParseError::InvalidRowSize(0));
+        }
+
+        // Validate and parse the rows into a map of permutations and their results
+        let values_and_results = rows_to_value_map(&rows)?;
 
         // Get the propositions based on the number of columns
         let propositions = get_propositions((rows[0].len() - 1) as u8);
 
-        // Parse the rows into a map of permutations and their results
-        let values_and_results = rows_to_value_map(rows);
+        Ok(Self::new(propositions, values_and_results))
+    }
+
+    // Parses a user-inputted set of rows into a truth table, ensuring that they contain the given number of propositions
+    pub fn parse_rows_with_count(rows: &str, proposition_count: u8) -> Self {
+        Self::try_parse_rows_with_count(rows, proposition_count)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Parses a user-inputted set of rows into a truth table, returning an error if they are invalid
+    // or do not contain the given number of propositions
+    pub fn try_parse_rows_with_count(
+        rows: &str,
+        proposition_count: u8,
+    ) -> Result<Self, RowParseError> {
+        let table = Self::try_parse_rows(rows)?;
+
+        if table.propositions.len() != proposition_count as usize {
+            return Err(RowParseError::PropositionCountMismatch {
+                expected: proposition_count,
+                found: table.propositions.len() as u8,
+            });
+        }
+
+        Ok(table)
+    }
+
+    // Parses a list of minterms such as "Σm(1, 3, 5, 7)" or "1,3,5,7" into a truth table, panicking if it is invalid
+    pub fn parse_minterms(minterms: &str, proposition_count: u8) -> Self {
+        Self::try_parse_minterms(minterms, proposition_count)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Parses a list of minterms into a truth table over the given number of propositions, returning an error if it is
+    // invalid
+    // Each minterm is the conventional index of a true row, where the first proposition is the most significant bit,
+    // and the "Σm" or "m" prefix and surrounding parentheses are optional
+    pub fn try_parse_minterms(
+        minterms: &str,
+        proposition_count: u8,
+    ) -> Result<Self, MintermParseError> {
+        if !(1..=PropositionIdentifier::MAX_COUNT).contains(&proposition_count) {
+            return Err(MintermParseError::InvalidPropositionCount(
+                proposition_count,
+            ));
+        }
+
+        let list = minterms.trim();
+        let list = list
+            .strip_prefix("Σm")
+            .or_else(|| list.strip_prefix('m'))
+            .unwrap_or(list)
+            .trim();
+        let list = list
+            .strip_prefix('(')
+            .and_then(|list| list.strip_suffix(')'))
+            .unwrap_or(list);
+
+        let row_count = 1u32 << proposition_count;
+        let mut true_rows = Vec::new();
+
+        for minterm in list.split(',').map(str::trim).filter(|m| !m.is_empty()) {
+            let index: u32 = minterm
+                .parse()
+                .map_err(|_| MintermParseError::InvalidMinterm(minterm.to_string()))?;
+
+            if index >= row_count {
+                return Err(MintermParseError::MintermOutOfRange { index, row_count });
+            }
+
+            true_rows.push(index);
+        }
+
+        let values_and_results = (0..row_count)
+            .map(|index| {
+                (
+                    index_to_permutation(index, proposition_count),
+                    true_rows.contains(&index),
+                )
+            })
+            .collect();
+
+        Ok(Self::new(
+            get_propositions(proposition_count),
+            values_and_results,
+        ))
+    }
+
+    // Creates a truth table with random results, which are reproducible for the same seed
+    pub fn random(proposition_count: u8, seed: u64) -> Self {
+        if proposition_count > PropositionIdentifier::MAX_COUNT {
+            panic!(
+                "Truth tables can contain at most {} propositions",
+                PropositionIdentifier::MAX_COUNT
+            );
+        }
+
+        let propositions = get_propositions(proposition_count);
+        let mut values_and_results = BTreeMap::new();
+        let mut state = seed;
+
+        for permutation in get_bit_permutations(proposition_count) {
+            values_and_results.insert(permutation, splitmix64(&mut state) & 1 == 1);
+        }
 
         Self::new(propositions, values_and_results)
     }
 
+    // Enumerates every boolean function of the given number of propositions, in order of their truth integers
+    // There are 2^(2^n) functions, so this is limited to the 4 propositions a truth integer can represent
+    pub fn all_functions(proposition_count: u8) -> impl Iterator<Item = TruthTable> {
+        if !(1..=4).contains(&proposition_count) {
+            panic!("Functions can only be enumerated for between 1 and 4 propositions");
+        }
+
+        (0..1u32 << (1 << proposition_count)).map(move |value| {
+            let mut expression = Expression::from_truth_integer(value as u16, proposition_count);
+            Self::from_expression(&mut expression)
+        })
+    }
+
+    // Adds a labeled column with the values of the subexpression at the given tree path for each permutation
+    // Each step of the path is the index of a subexpression in the elements of the previous expression
+    // Returns an error without adding a column if the path does not lead to a subexpression
+    pub fn with_subexpression_column(
+        &mut self,
+        expression: &mut Expression,
+        path: &[usize],
+    ) -> Result<(), InvalidSubexpressionPath> {
+        let label = expression.subexpression_label(path)?;
+
+        let values = self
+            .values_and_results
+            .keys()
+            .map(|permutation| {
+                let value = expression.evaluate_subexpression_permutation(path, *permutation)?;
+                Ok((*permutation, value))
+            })
+            .collect::<Result<_, _>>()?;
+
+        self.subexpression_columns.push((label, values));
+
+        Ok(())
+    }
+
     // Converts the truth table into a string representation of the expression
     pub fn to_disjunction(&self) -> String {
-        // Get the number of propositions in the truth table
-        let proposition_count = self.propositions.len() as u8;
+        self.encode_rows(true)
+    }
 
-        // Add a conjunction for each permutation that evaluates to true
+    // Converts the truth table into a string representation of the expression, returning an error if it would have
+    // more conjunctions than the given budget
+    pub fn try_to_disjunction(&self, max_terms: usize) -> Result<String, TooManyTerms> {
+        let terms = self
+            .values_and_results
+            .values()
+            .filter(|result| **result)
+            .count();
+
+        if terms > max_terms {
+            return Err(TooManyTerms { max_terms, terms });
+        }
+
+        Ok(self.to_disjunction())
+    }
+
+    // Converts the truth table into a string representation of its complement, which is true wherever the table is false
+    // This can be simpler than the expression itself when the table is mostly true
+    pub fn to_complement_disjunction(&self) -> String {
+        self.encode_rows(false)
+    }
+
+    // Encodes all permutations with the given result into a disjunction of conjunctions
+    fn encode_rows(&self, target: bool) -> String {
+        // Add a conjunction for each permutation that evaluates to the target result
         let mut expression = String::new();
 
         for (permutation, result) in &self.values_and_results {
-            if *result {
+            if *result == target {
                 if !expression.is_empty() {
                     expression.push_str(" | ");
                 }
 
-                expression.push_str(encode_conjunction(*permutation, proposition_count).as_str());
+                expression.push_str(&encode_conjunction(*permutation, &self.propositions));
             }
         }
 
         expression
     }
 
+    // Converts the truth table into a product of sums, which is a conjunction of a disjunction for each false row
+    // A tautology has no false rows, so it becomes an empty string like a contradiction does in to_disjunction
+    pub fn to_conjunction(&self) -> String {
+        let disjunctions: Vec<String> = self
+            .values_and_results
+            .iter()
+            .filter(|(_, result)| !**result)
+            .map(|(permutation, _)| encode_disjunction(*permutation, &self.propositions))
+            .collect();
+
+        disjunctions.join(" & ")
+    }
+
+    // Converts the truth table into a minimal disjunction of conjunctions, such as "A | (!B & C)"
+    // A tautology becomes the constant "1", and a contradiction becomes "0"
+    pub fn to_minimized_dnf(&self) -> String {
+        self.to_minimized_dnf_with_dont_cares(&[])
+    }
+
+    // Converts the truth table into a minimal disjunction of conjunctions, where the rows at the given conventional
+    // indices are don't-cares that may be covered or left out, whatever their results are
+    pub fn to_minimized_dnf_with_dont_cares(&self, dont_cares: &[u32]) -> String {
+        let minterms: Vec<u32> = self
+            .rows_by_index()
+            .filter(|(index, _, result)| *result && !dont_cares.contains(index))
+            .map(|(index, _, _)| index)
+            .collect();
+
+        let implicants = minimize(&minterms, dont_cares, self.propositions.len() as u8);
+
+        // Without any implicants, no row is true
+        if implicants.is_empty() {
+            return String::from("0");
+        }
+
+        let terms: Vec<String> = implicants
+            .iter()
+            .map(|implicant| self.encode_implicant(implicant))
+            .collect();
+
+        terms.join(" | ")
+    }
+
+    // Encodes an implicant into a conjunction of its literals, which is only parenthesized if it has multiple literals
+    fn encode_implicant(&self, implicant: &Implicant) -> String {
+        let proposition_count = self.propositions.len();
+        let mut literals = Vec::new();
+
+        for (i, proposition) in self.propositions.iter().enumerate() {
+            let bit = 1 << (proposition_count - 1 - i);
+
+            // Skip propositions which were eliminated from the implicant
+            if implicant.mask & bit != 0 {
+                continue;
+            }
+
+            if implicant.value & bit != 0 {
+                literals.push(proposition.to_string());
+            } else {
+                literals.push(format!("!{}", proposition));
+            }
+        }
+
+        match literals.len() {
+            // An implicant with no literals is always true
+            0 => String::from("1"),
+            1 => literals.remove(0),
+            _ => format!("({})", literals.join(" & ")),
+        }
+    }
+
+    // Converts the truth table into a Verilog continuous assignment of its minimized expression, such as "assign f = (A & ~B);"
+    pub fn to_verilog(&self, name: &str) -> String {
+        let expression = if self.values_and_results.values().all(|result| *result) {
+            String::from("1'b1")
+        } else if self.values_and_results.values().all(|result| !*result) {
+            String::from("1'b0")
+        } else {
+            self.to_minimized_dnf().replace('!', "~")
+        };
+
+        format!("assign {} = {};", name, expression)
+    }
+
     // Parses a user-inputted string into an Expression, then into a truth table
     pub fn parse_expression_str(expression: &str) -> Self {
         let mut expression = Expression::parse(expression, true);
         Self::from_expression(&mut expression)
     }
 
-    // Formats and prints the truth table
-    pub fn print(&self) {
-        let mut num_dividers = 8;
+    // Parses a user-inputted string into an Expression, then into a truth table over only the propositions it uses
+    // Unlike parse_expression_str, the propositions do not need to be consecutive
+    pub fn parse_expression_str_with_gaps(expression: &str) -> Self {
+        let mut expression = Expression::parse(expression, false);
+        Self::from_expression(&mut expression)
+    }
 
-        // Print the header
-        for proposition in &self.propositions {
-            print!("{} ", proposition.to_char());
-            num_dividers += 2;
+    // Parses a user-inputted string into an Expression, then into a truth table with a column for each subexpression
+    // Inner subexpressions are shown before the subexpressions containing them
+    pub fn parse_expression_str_verbose(expression: &str) -> Self {
+        let mut expression = Expression::parse(expression, true);
+        Self::from_expression_verbose(&mut expression)
+    }
+
+    // Creates a new truth table for a given expression, with a column for each subexpression
+    // Inner subexpressions are shown before the subexpressions containing them
+    pub fn from_expression_verbose(expression: &mut Expression) -> Self {
+        let mut table = Self::from_expression(expression);
+
+        for path in expression.subexpression_paths() {
+            table
+                .with_subexpression_column(expression, &path)
+                .expect("[INTERNAL ERROR] Subexpression paths do not lead to subexpressions");
         }
 
-        println!("│ Result");
+        table
+    }
 
-        // Print the dividers
-        for i in 0..num_dividers {
-            if i == num_dividers - 8 {
-                print!("┼");
-            } else {
-                print!("─");
-            }
+    // Identifies which standard two-input gate the truth table represents, if any
+    pub fn identify_gate(&self) -> Option<GateKind> {
+        if self.propositions.len() != 2 {
+            return None;
+        }
+
+        // Rows are stored in the order 00, 01, 10, 11
+        let results: Vec<bool> = self.values_and_results.values().copied().collect();
+
+        use GateKind::*;
+
+        match results.as_slice() {
+            [false, false, false, true] => Some(And),
+            [false, true, true, true] => Some(Or),
+            [false, true, true, false] => Some(Xor),
+            [true, true, true, false] => Some(Nand),
+            [true, false, false, false] => Some(Nor),
+            [true, false, false, true] => Some(Xnor),
+            _ => None,
         }
+    }
 
-        println!();
+    // Lists the Venn diagram regions included by the truth table, where each true row represents a region
+    pub fn venn_regions(&self) -> Vec<String> {
+        let mut regions = Vec::new();
 
-        // Print the values and results
         for (permutation, result) in &self.values_and_results {
-            for proposition in &self.propositions {
-                let proposition_bit = proposition.mask(*permutation) as u8;
-                print!("{} ", proposition_bit);
+            if !*result {
+                continue;
             }
 
-            println!("│      {}", if *result { "T" } else { "F" });
+            // Get the sets which contain the region
+            let sets: Vec<String> = self
+                .propositions
+                .iter()
+                .zip(self.values_of(*permutation))
+                .filter(|(_, value)| *value)
+                .map(|(proposition, _)| proposition.to_string())
+                .collect();
+
+            // Name the region based on which sets it is inside of
+            let region = if sets.is_empty() {
+                match self.propositions.len() {
+                    2 => String::from("neither"),
+                    _ => String::from("none"),
+                }
+            } else if sets.len() == self.propositions.len() {
+                sets.join("∩")
+            } else {
+                format!("{} only", sets.join("∩"))
+            };
+
+            regions.push(region);
         }
 
-        println!();
+        regions
     }
-}
 
-// Checks a set of rows against formatting requirements
-fn validate_rows(rows: &Vec<&str>) {
-    // Make sure all rows contain only '0' and '1'
-    for row in rows {
-        for c in row.chars() {
-            if c != '0' && c != '1' {
-                panic!("Invalid character '{}' found in row '{}'", c, row);
-            }
-        }
+    // Checks whether every row of the truth table is true
+    pub fn is_tautology(&self) -> bool {
+        self.values_and_results.values().all(|result| *result)
     }
 
-    // Make sure all rows are the same length, and that they are within the range of 2 to 5
-    let row_size = rows[0].len();
+    // Checks whether every row of the truth table is false
+    pub fn is_contradiction(&self) -> bool {
+        self.values_and_results.values().all(|result| !*result)
+    }
 
-    if row_size < 2 || row_size > 5 {
-        panic!("Row size must be between 2 and 5, representing up to four proposition rows and one result row");
+    // Checks whether at least one row of the truth table is true
+    pub fn is_satisfiable(&self) -> bool {
+        !self.is_contradiction()
     }
 
-    for row in rows {
-        if row.len() != row_size {
-            panic!("All rows must be the same length");
+    // Returns the number of rows which are true
+    pub fn true_count(&self) -> usize {
+        self.values_and_results
+            .values()
+            .filter(|result| **result)
+            .count()
+    }
+
+    // Returns the number of rows which are false
+    pub fn false_count(&self) -> usize {
+        self.values_and_results.len() - self.true_count()
+    }
+
+    // Returns the fraction of rows which are true, which is the probability of the result being true for
+    // uniformly random propositions
+    pub fn truth_ratio(&self) -> f64 {
+        self.true_count() as f64 / self.values_and_results.len() as f64
+    }
+
+    // Returns all permutations which evaluate to true, in row order
+    pub fn satisfying_assignments(&self) -> Vec<u32> {
+        self.values_and_results
+            .iter()
+            .filter(|(_, result)| **result)
+            .map(|(permutation, _)| *permutation)
+            .collect()
+    }
+
+    // Returns how many single-proposition flips of the given permutation change the result, or None if the
+    // permutation is not in the table
+    // Flips leading to rows missing from the table are not counted
+    pub fn sensitivity_at(&self, permutation: u32) -> Option<usize> {
+        let result = self.get_result(permutation)?;
+
+        let sensitivity = (0..self.propositions.len())
+            .filter(|position| {
+                self.get_result(permutation ^ proposition_bit(*position))
+                    .is_some_and(|flipped_result| flipped_result != result)
+            })
+            .count();
+
+        Some(sensitivity)
+    }
+
+    // Returns the number of rows whose results differ from another truth table over the same propositions
+    pub fn hamming_distance(&self, other: &TruthTable) -> Option<usize> {
+        if self.propositions != other.propositions {
+            return None;
         }
+
+        let distance = self
+            .values_and_results
+            .iter()
+            .filter(|(permutation, result)| other.get_result(**permutation) != Some(**result))
+            .count();
+
+        Some(distance)
     }
-}
 
-// Gets a list of propositions based on the given count
-// It is assumed that the propositions are named A, B, C, and D, and will never be out of order
-fn get_propositions(proposition_count: u8) -> Vec<PropositionIdentifier> {
-    let mut propositions = Vec::new();
-    
-    for i in 0..proposition_count {
-        propositions.push(PropositionIdentifier::from_int(i));
+    // Computes the Walsh-Hadamard transform of the table's results encoded as +1 (false) and -1 (true)
+    // Each coefficient is indexed by the conventional row index of the linear function it correlates with
+    pub fn walsh_coefficients(&self) -> Vec<i32> {
+        let truth_vector = self.truth_vector();
+
+        (0..truth_vector.len())
+            .map(|s| {
+                truth_vector
+                    .iter()
+                    .enumerate()
+                    .map(|(x, result)| {
+                        // The sign flips for each set bit shared by the linear function and the row, and for a true result
+                        if ((s & x).count_ones() % 2 == 1) != *result {
+                            -1
+                        } else {
+                            1
+                        }
+                    })
+                    .sum()
+            })
+            .collect()
     }
-    
-    propositions
-}
 
-// Parses a set of string-encoded rows into a map of permutations and their results
-fn rows_to_value_map(rows: Vec<&str>) -> BTreeMap<u8, bool> {
-    // Ensure the rows are valid before attempting to parse them
-    validate_rows(&rows);
+    // Returns the results of all rows, ordered by their conventional row index
+    fn truth_vector(&self) -> Vec<bool> {
+        self.rows_by_index().map(|(_, _, result)| result).collect()
+    }
 
-    let mut values_and_results = BTreeMap::new();
+    // Returns the propositions of the truth table, in column order
+    pub fn propositions(&self) -> &[PropositionIdentifier] {
+        &self.propositions
+    }
 
-    for row in rows {
-        let permutation = decode_permutation_str(row);
-        let result = row.chars().last().unwrap() == '1';
+    /// Iterates over the permutations and results of all rows, in the same order that they are printed
+    ///
+    /// ```
+    /// use truth_tables::TruthTable;
+    ///
+    /// let table = TruthTable::parse_expression_str("A & B");
+    ///
+    /// for (permutation, result) in table.rows() {
+    ///     assert_eq!(result, table.values_of(permutation) == [true, true]);
+    /// }
+    ///
+    /// assert_eq!(table.rows().filter(|(_, result)| *result).count(), 1);
+    /// ```
+    pub fn rows(&self) -> impl Iterator<Item = (u32, bool)> + '_ {
+        self.values_and_results
+            .iter()
+            .map(|(permutation, result)| (*permutation, *result))
+    }
 
-        values_and_results.insert(permutation, result);
+    // Iterates over the rows in ascending conventional index order, regardless of how the permutations are stored
+    // Each row contains its index, the values of its propositions, and its result
+    pub fn rows_by_index(&self) -> impl Iterator<Item = (u32, Vec<bool>, bool)> + '_ {
+        let mut rows: Vec<(u32, u32, bool)> = self
+            .values_and_results
+            .iter()
+            .map(|(permutation, result)| {
+                (self.permutation_index(*permutation), *permutation, *result)
+            })
+            .collect();
+
+        rows.sort();
+
+        rows.into_iter()
+            .map(|(index, permutation, result)| (index, self.values_of(permutation), result))
     }
 
-    values_and_results
-}
+    // Returns the values of the propositions in a permutation, in column order
+    pub fn values_of(&self, permutation: u32) -> Vec<bool> {
+        (0..self.propositions.len())
+            .map(|position| permutation & proposition_bit(position) != 0)
+            .collect()
+    }
 
-// Takes a string-encoded row and decodes it into a value permutation
-fn decode_permutation_str(row: &str) -> u8 {
-    // Last character is the result, so it is ignored
-    let row = &row[0..row.len() - 1];
+    // Returns the results keyed by conventional row index, where the first proposition is the most significant bit
+    // This is the form most external tools expect, unlike the left-aligned permutations used internally
+    pub fn to_standard_keyed_map(&self) -> BTreeMap<u32, bool> {
+        self.rows_by_index()
+            .map(|(index, _, result)| (index, result))
+            .collect()
+    }
 
-    // Find the number of propositions
-    let proposition_count = row.len();
+    // Returns the smallest truth integer of the table over every reordering of its propositions
+    // Functions which are identical up to renaming their propositions share this signature
+    pub fn canonical_under_permutation(&self) -> u16 {
+        let proposition_count = self.propositions.len();
 
-    // Convert to bits and shift based on amount of skipped propositions (0bA/0bAB/0bABC/0bABCD -> 0b0000ABCD)
-    u8::from_str_radix(row, 2).unwrap() << (4 - proposition_count)
-}
+        if proposition_count > 4 {
+            panic!("Truth integers can only represent between 1 and 4 propositions");
+        }
 
-// Takes a value permutation and encodes it into a conjunction in parentheses such as "(A & B & C)"
-fn encode_conjunction(permutation: u8, proposition_count: u8) -> String {
-    let mut conjunction = String::from('(');
+        let truth_vector = self.truth_vector();
 
-    for i in 0..proposition_count {
-        let proposition = PropositionIdentifier::from_int(i);
+        get_orderings(proposition_count)
+            .iter()
+            .map(|ordering| {
+                let mut value = 0;
+
+                for (index, result) in truth_vector.iter().enumerate() {
+                    if !*result {
+                        continue;
+                    }
+
+                    // Move the bit of each proposition to its new position, where position 0 is the most significant bit
+                    let mut reordered = 0;
+
+                    for (from, to) in ordering.iter().enumerate() {
+                        if index & (1 << (proposition_count - 1 - from)) != 0 {
+                            reordered |= 1 << (proposition_count - 1 - to);
+                        }
+                    }
 
-        // Add operators between propositions
-        if conjunction != "(" && i != proposition_count {
-            conjunction.push_str(" & ");
+                    value |= 1 << reordered;
+                }
+
+                value
+            })
+            .min()
+            .unwrap()
+    }
+
+    // Returns the conventional indices of the true rows and of the false rows, each in ascending order
+    pub fn on_off_sets(&self) -> (Vec<u32>, Vec<u32>) {
+        let mut on_set = Vec::new();
+        let mut off_set = Vec::new();
+
+        for (index, _, result) in self.rows_by_index() {
+            if result {
+                on_set.push(index);
+            } else {
+                off_set.push(index);
+            }
         }
 
-        // If the proposition is false, denote that it is negated
-        if !proposition.mask(permutation) {
-            conjunction.push('!');
+        (on_set, off_set)
+    }
+
+    // Renders the truth table as a sum of minterms, such as "f(A,B,C) = Σm(1,3,7)"
+    pub fn to_minterm_notation(&self) -> String {
+        format!("{} = Σm({})", self.function_signature(), self.index_list(true))
+    }
+
+    // Renders the truth table as a product of maxterms, such as "f(A,B) = ΠM(0)"
+    pub fn to_maxterm_notation(&self) -> String {
+        format!("{} = ΠM({})", self.function_signature(), self.index_list(false))
+    }
+
+    // Returns the function signature listing the table's propositions, such as "f(A,B,C)"
+    fn function_signature(&self) -> String {
+        let propositions: Vec<String> = self
+            .propositions
+            .iter()
+            .map(PropositionIdentifier::to_string)
+            .collect();
+
+        format!("f({})", propositions.join(","))
+    }
+
+    // Returns a comma-separated list of the conventional indices of all rows with the given result
+    fn index_list(&self, result: bool) -> String {
+        let mut indices: Vec<u32> = self
+            .values_and_results
+            .iter()
+            .filter(|(_, r)| **r == result)
+            .map(|(permutation, _)| self.permutation_index(*permutation))
+            .collect();
+
+        indices.sort();
+
+        let indices: Vec<String> = indices.iter().map(u32::to_string).collect();
+        indices.join(",")
+    }
+
+    // Converts a permutation into its conventional row index, where the first proposition is the most significant bit
+    fn permutation_index(&self, permutation: u32) -> u32 {
+        permutation_to_index(permutation, self.propositions.len() as u8)
+    }
+
+    // Returns the propositions which the result actually depends on
+    // A proposition is in the support if flipping it changes the result of at least one row
+    pub fn support(&self) -> Vec<PropositionIdentifier> {
+        self.propositions
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| {
+                self.values_and_results.iter().any(|(permutation, result)| {
+                    self.get_result(permutation ^ proposition_bit(*position))
+                        .is_some_and(|flipped_result| flipped_result != *result)
+                })
+            })
+            .map(|(_, proposition)| proposition.clone())
+            .collect()
+    }
+
+    // Checks whether the function is unate in a proposition by comparing its cofactors
+    // Returns Some(true) if it is positive unate (never decreases when the proposition becomes true),
+    // Some(false) if it is negative unate, and None if it is binate
+    // A function which does not depend on the proposition is both, and is reported as positive unate
+    // Returns an error if the proposition is not in the table
+    pub fn is_unate_in(
+        &self,
+        proposition: PropositionIdentifier,
+    ) -> Result<Option<bool>, MissingProposition> {
+        let bit = match self.propositions.iter().position(|p| *p == proposition) {
+            Some(position) => proposition_bit(position),
+            None => return Err(MissingProposition(proposition)),
+        };
+
+        let mut is_positive = true;
+        let mut is_negative = true;
+
+        // Compare each row where the proposition is false against the same row where it is true
+        for (permutation, result) in &self.values_and_results {
+            if permutation & bit != 0 {
+                continue;
+            }
+
+            let flipped_result = match self.get_result(permutation | bit) {
+                Some(flipped_result) => flipped_result,
+                None => continue,
+            };
+
+            is_positive &= !*result || flipped_result;
+            is_negative &= *result || !flipped_result;
         }
 
-        // Append the proposition letter
-        conjunction.push(proposition.to_char());
+        Ok(match (is_positive, is_negative) {
+            (true, _) => Some(true),
+            (false, true) => Some(false),
+            (false, false) => None,
+        })
     }
 
-    conjunction.push(')');
+    // Checks whether the result depends only on how many propositions are true
+    pub fn is_symmetric(&self) -> bool {
+        self.symmetric_spec().is_some()
+    }
 
-    conjunction
-}
+    // Returns the result for each possible count of true propositions from 0 to n, if the function is symmetric
+    pub fn symmetric_spec(&self) -> Option<Vec<bool>> {
+        let mut popcount_results: Vec<Option<bool>> = vec![None; self.propositions.len() + 1];
 
-// Gets a range of numbers with all possible permutations of a given number of bits
-fn get_bit_permutations(bits: u8) -> Vec<u8> {
-    let mut permutations = Vec::new();
+        for (permutation, result) in &self.values_and_results {
+            let popcount_result = &mut popcount_results[permutation.count_ones() as usize];
+
+            match popcount_result {
+                Some(r) if r != result => return None,
+                _ => *popcount_result = Some(*result),
+            }
+        }
 
-    for i in 0u8..(1 << bits) {
-        permutations.push(i.reverse_bits() >> 4);
+        popcount_results.into_iter().collect()
     }
 
-    permutations
-}
+    // Checks whether complementing every proposition never changes the result, so f(!x) == f(x) for all x
+    pub fn is_anti_self_dual(&self) -> bool {
+        let proposition_count = self.propositions.len() as u8;
+        let all_propositions =
+            index_to_permutation((1 << proposition_count) - 1, proposition_count);
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+        self.values_and_results.iter().all(|(permutation, result)| {
+            self.get_result(permutation ^ all_propositions) == Some(*result)
+        })
+    }
 
-    #[test]
-    fn test_get_bit_permutations() {
-        assert_eq!(get_bit_permutations(0), vec![0b0000]);
-        assert_eq!(get_bit_permutations(1), vec![0b0000, 0b1000]);
-        assert_eq!(get_bit_permutations(2), vec![0b0000, 0b1000, 0b0100, 0b1100]);
-        assert_eq!(get_bit_permutations(3), vec![0b0000, 0b1000, 0b0100, 0b1100, 0b0010, 0b1010, 0b0110, 0b1110]);
-        assert_eq!(get_bit_permutations(4), vec![0b0000, 0b1000, 0b0100, 0b1100, 0b0010, 0b1010, 0b0110, 0b1110, 0b0001, 0b1001, 0b0101, 0b1101, 0b0011, 0b1011, 0b0111, 0b1111]);
+    // Returns the result of the given permutation, or None if it is not in the table
+    fn get_result(&self, permutation: u32) -> Option<bool> {
+        self.values_and_results.get(&permutation).copied()
     }
 
-    #[test]
-    fn test_decode_permutations() {
-        assert_eq!(decode_permutation_str("01"), 0b0000);
-        assert_eq!(decode_permutation_str("11"), 0b1000);
-        assert_eq!(decode_permutation_str("101"), 0b1000);
-        assert_eq!(decode_permutation_str("111"), 0b1100);
-        assert_eq!(decode_permutation_str("011"), 0b0100);
-        assert_eq!(decode_permutation_str("1001"), 0b1000);
-        assert_eq!(decode_permutation_str("1011"), 0b1010);
-        assert_eq!(decode_permutation_str("1101"), 0b1100);
-        assert_eq!(decode_permutation_str("1111"), 0b1110);
-        assert_eq!(decode_permutation_str("10001"), 0b1000);
-        assert_eq!(decode_permutation_str("10011"), 0b1001);
-        assert_eq!(decode_permutation_str("10101"), 0b1010);
-        assert_eq!(decode_permutation_str("10111"), 0b1011);
-        assert_eq!(decode_permutation_str("11001"), 0b1100);
-        assert_eq!(decode_permutation_str("11011"), 0b1101);
-        assert_eq!(decode_permutation_str("11101"), 0b1110);
-        assert_eq!(decode_permutation_str("11111"), 0b1111);
+    // Formats and prints the results of two truth tables side by side, marking the rows where they differ
+    // Returns an error without printing anything if the tables contain different propositions
+    pub fn print_comparison(&self, other: &TruthTable) -> Result<(), ComparisonError> {
+        print!("{}", self.to_comparison_string(other)?);
+
+        Ok(())
     }
 
-    #[test]
-    fn test_encode_conjunction() {
-        assert_eq!(encode_conjunction(0b0000, 1), "(!A)");
-        assert_eq!(encode_conjunction(0b1000, 1), "(A)");
-        assert_eq!(encode_conjunction(0b0100, 2), "(!A & B)");
-        assert_eq!(encode_conjunction(0b1100, 2), "(A & B)");
-        assert_eq!(encode_conjunction(0b0010, 3), "(!A & !B & C)");
-        assert_eq!(encode_conjunction(0b1010, 3), "(A & !B & C)");
-        assert_eq!(encode_conjunction(0b0110, 3), "(!A & B & C)");
-        assert_eq!(encode_conjunction(0b1110, 3), "(A & B & C)");
-        assert_eq!(encode_conjunction(0b0001, 4), "(!A & !B & !C & D)");
-        assert_eq!(encode_conjunction(0b1001, 4), "(A & !B & !C & D)");
-        assert_eq!(encode_conjunction(0b0101, 4), "(!A & B & !C & D)");
-        assert_eq!(encode_conjunction(0b1101, 4), "(A & B & !C & D)");
-        assert_eq!(encode_conjunction(0b0011, 4), "(!A & !B & C & D)");
-        assert_eq!(encode_conjunction(0b1011, 4), "(A & !B & C & D)");
-        assert_eq!(encode_conjunction(0b0111, 4), "(!A & B & C & D)");
-        assert_eq!(encode_conjunction(0b1111, 4), "(A & B & C & D)");
+    // Formats the results of two truth tables side by side into a string, marking the rows where they differ
+    // Returns an error if the tables contain different propositions
+    pub fn to_comparison_string(&self, other: &TruthTable) -> Result<String, ComparisonError> {
+        self.format_comparison(other, false)
     }
 
-    #[test]
-    fn test_to_disjunction() {
-        let table = TruthTable::parse_expression_str("A & B & C & D");
-        assert_eq!(table.to_disjunction(), "(A & B & C & D)");
+    // Returns each permutation where the results of two truth tables disagree, along with both results
+    // Returns an error if the tables contain different propositions
+    pub fn diff(&self, other: &TruthTable) -> Result<Vec<(u32, bool, bool)>, ComparisonError> {
+        if self.propositions != other.propositions {
+            return Err(ComparisonError::new(self, other));
+        }
 
-        let table = TruthTable::parse_expression_str("A & B & C & !D");
-        assert_eq!(table.to_disjunction(), "(A & B & C & !D)");
+        let differences = self
+            .values_and_results
+            .iter()
+            .filter_map(|(permutation, result)| {
+                Some((*permutation, *result, other.get_result(*permutation)?))
+            })
+            .filter(|(_, result, other_result)| result != other_result)
+            .collect();
 
-        let table = TruthTable::parse_expression_str("A & B & !C & D");
-        assert_eq!(table.to_disjunction(), "(A & B & !C & D)");
+        Ok(differences)
+    }
 
-        let table = TruthTable::parse_expression_str("A & B & !C & !D");
-        assert_eq!(table.to_disjunction(), "(A & B & !C & !D)");
+    // Formats and prints only the rows where the results of two truth tables disagree
+    // Returns an error without printing anything if the tables contain different propositions
+    pub fn print_diff(&self, other: &TruthTable) -> Result<(), ComparisonError> {
+        print!("{}", self.to_diff_string(other)?);
 
-        let table = TruthTable::parse_expression_str("A & !B & C & D");
-        assert_eq!(table.to_disjunction(), "(A & !B & C & D)");
+        Ok(())
+    }
 
-        let table = TruthTable::parse_expression_str("(A & B) | C");
-        assert_eq!(table.to_disjunction(), "(!A & !B & C) | (!A & B & C) | (A & !B & C) | (A & B & !C) | (A & B & C)");
+    // Formats only the rows where the results of two truth tables disagree into a string
+    // If the tables are equivalent, a message is shown in place of the rows
+    // Returns an error if the tables contain different propositions
+    pub fn to_diff_string(&self, other: &TruthTable) -> Result<String, ComparisonError> {
+        let mut output = self.format_comparison(other, true)?;
+
+        if self.diff(other)?.is_empty() {
+            output.insert_str(output.len() - 1, "No rows differ\n");
+        }
+
+        Ok(output)
+    }
+
+    // Formats the header and the rows of two truth tables side by side, optionally only including rows which differ
+    fn format_comparison(
+        &self,
+        other: &TruthTable,
+        only_differences: bool,
+    ) -> Result<String, ComparisonError> {
+        if self.propositions != other.propositions {
+            return Err(ComparisonError::new(self, other));
+        }
+
+        let mut output = String::new();
+
+        // Print the header
+        for proposition in &self.propositions {
+            output.push_str(&format!("{} ", proposition));
+        }
+
+        if only_differences {
+            output.push_str("│ Left Right\n");
+        } else {
+            output.push_str("│ Left Right Diff\n");
+        }
+
+        // Print the dividers, where each column is as wide as its proposition's name
+        let header_width: usize = self
+            .propositions
+            .iter()
+            .map(|proposition| proposition.name().len() + 1)
+            .sum();
+
+        output.push_str(&"─".repeat(header_width));
+        output.push('┼');
+        output.push_str(&"─".repeat(if only_differences { 11 } else { 16 }));
+        output.push('\n');
+
+        // Print the values and both results, marking any disagreement
+        for (permutation, result) in &self.values_and_results {
+            let other_result = match other.get_result(*permutation) {
+                Some(other_result) => other_result,
+                None => continue,
+            };
+
+            if only_differences && *result == other_result {
+                continue;
+            }
+
+            let mut row = String::new();
+
+            for (proposition, value) in self.propositions.iter().zip(self.values_of(*permutation)) {
+                row.push_str(&format!(
+                    "{:<width$} ",
+                    value as u8,
+                    width = proposition.name().len()
+                ));
+            }
+
+            row.push_str(&format!(
+                "│ {:>4} {:>5} {:>4}",
+                if *result { "T" } else { "F" },
+                if other_result { "T" } else { "F" },
+                if *result != other_result && !only_differences {
+                    "*"
+                } else {
+                    ""
+                }
+            ));
+
+            output.push_str(row.trim_end());
+            output.push('\n');
+        }
+
+        output.push('\n');
+
+        Ok(output)
+    }
+
+    // Formats and prints the truth table
+    pub fn print(&self) {
+        self.print_styled(&TableStyle::default());
+    }
+
+    // Formats and prints the truth table using the given style options
+    pub fn print_styled(&self, style: &TableStyle) {
+        print!("{}", self.to_styled_string(style));
+    }
+
+    // Formats the truth table into a string using the given style options
+    pub fn to_styled_string(&self, style: &TableStyle) -> String {
+        self.format_rows(style, |_, _| true)
+    }
+
+    // Formats the truth table as CSV, with a header of proposition names and "Result", and a row of 0s and 1s
+    // for each permutation
+    pub fn to_csv(&self) -> String {
+        let mut header: Vec<String> = self
+            .propositions
+            .iter()
+            .map(PropositionIdentifier::to_string)
+            .collect();
+        header.push(String::from("Result"));
+
+        let mut output = header.join(",");
+        output.push('\n');
+
+        for (permutation, result) in &self.values_and_results {
+            for value in self.values_of(*permutation) {
+                output.push_str(&format!("{},", value as u8));
+            }
+
+            output.push_str(&format!("{}\n", *result as u8));
+        }
+
+        output
+    }
+
+    // Formats the truth table as a JSON array with an object for each permutation, such as
+    // {"A": true, "B": false, "Result": false}
+    pub fn to_json(&self) -> String {
+        let rows: Vec<String> = self
+            .values_and_results
+            .iter()
+            .map(|(permutation, result)| {
+                let mut fields: Vec<String> = self
+                    .propositions
+                    .iter()
+                    .zip(self.values_of(*permutation))
+                    .map(|(proposition, value)| format!("\"{}\": {}", proposition, value))
+                    .collect();
+                fields.push(format!("\"Result\": {}", result));
+
+                format!("  {{{}}}", fields.join(", "))
+            })
+            .collect();
+
+        format!("[\n{}\n]\n", rows.join(",\n"))
+    }
+
+    // Writes the truth table as JSON lines, with an object such as {"A":0,"B":1,"result":1} for each permutation
+    #[cfg(feature = "serde")]
+    pub fn write_jsonl<W: std::io::Write>(&self, w: &mut W) -> std::io::Result<()> {
+        for (permutation, result) in &self.values_and_results {
+            let mut row = serde_json::Map::new();
+
+            for (proposition, value) in self.propositions.iter().zip(self.values_of(*permutation)) {
+                row.insert(proposition.to_string(), (value as u8).into());
+            }
+
+            row.insert(String::from("result"), (*result as u8).into());
+
+            writeln!(w, "{}", serde_json::Value::Object(row))?;
+        }
+
+        Ok(())
+    }
+
+    // Formats the truth table as an Org-mode table, such as "| A | B | Result |" followed by a "|---+---+--------|" rule
+    pub fn to_org(&self) -> String {
+        let mut header: Vec<String> = self
+            .propositions
+            .iter()
+            .map(PropositionIdentifier::to_string)
+            .collect();
+        header.push(String::from("Result"));
+
+        let rule: Vec<String> = header
+            .iter()
+            .map(|label| "-".repeat(label.chars().count() + 2))
+            .collect();
+
+        let mut output = format!("| {} |\n|{}|\n", header.join(" | "), rule.join("+"));
+
+        for (permutation, result) in &self.values_and_results {
+            for (label, value) in header.iter().zip(self.values_of(*permutation)) {
+                output.push_str(&format!(
+                    "| {:<width$} ",
+                    value as u8,
+                    width = label.chars().count()
+                ));
+            }
+
+            output.push_str(&format!("| {:<6} |\n", if *result { "T" } else { "F" }));
+        }
+
+        output
+    }
+
+    // Formats and prints the header and a single row of the truth table, selected by its conventional index
+    // Panics if the table does not contain the row
+    pub fn print_row(&self, index: u32) {
+        print!("{}", self.to_row_string(index));
+    }
+
+    // Formats and prints the header and a single row of the truth table, selected by its conventional index
+    // Returns an error without printing anything if the table does not contain the row
+    pub fn try_print_row(&self, index: u32) -> Result<(), RowOutOfRange> {
+        print!("{}", self.try_to_row_string(index)?);
+
+        Ok(())
+    }
+
+    // Formats the header and a single row of the truth table into a string, selected by its conventional index
+    // Panics if the table does not contain the row
+    pub fn to_row_string(&self, index: u32) -> String {
+        self.try_to_row_string(index)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    // Formats the header and a single row of the truth table into a string, selected by its conventional index
+    // Returns an error if the table does not contain the row
+    pub fn try_to_row_string(&self, index: u32) -> Result<String, RowOutOfRange> {
+        let permutation = self
+            .values_and_results
+            .keys()
+            .find(|permutation| self.permutation_index(**permutation) == index)
+            .copied()
+            .ok_or(RowOutOfRange {
+                index,
+                row_count: self.values_and_results.len(),
+            })?;
+
+        Ok(self.format_rows(&TableStyle::default(), |p, _| p == permutation))
+    }
+
+    // Formats and prints only the rows of the truth table with the given result
+    pub fn print_filtered(&self, result: bool) {
+        print!("{}", self.to_filtered_string(result));
+    }
+
+    // Formats only the rows of the truth table with the given result into a string
+    // If no rows have the result, a message is shown in place of the rows
+    pub fn to_filtered_string(&self, result: bool) -> String {
+        let mut output = self.format_rows(&TableStyle::default(), |_, r| r == result);
+
+        if !self.values_and_results.values().any(|r| *r == result) {
+            let message = format!("No rows are {}\n", if result { "true" } else { "false" });
+            output.insert_str(output.len() - 1, &message);
+        }
+
+        output
+    }
+
+    // Formats the header and all rows accepted by the filter, which is given each row's permutation and result
+    fn format_rows(&self, style: &TableStyle, filter: impl Fn(u32, bool) -> bool) -> String {
+        let mut output = String::new();
+        let proposition_count = self.propositions.len();
+
+        // Get the header label for each proposition column
+        let labels: Vec<String> = self
+            .propositions
+            .iter()
+            .enumerate()
+            .map(|(i, proposition)| {
+                if style.show_weights {
+                    format!("{}({})", proposition, 1 << (proposition_count - 1 - i))
+                } else {
+                    proposition.to_string()
+                }
+            })
+            .collect();
+
+        let mut num_dividers = 8;
+
+        // Print the header
+        for label in &labels {
+            output.push_str(&format!("{}{}", label, style.separator));
+            num_dividers += label.chars().count() + style.separator.chars().count();
+        }
+
+        for (label, _) in &self.subexpression_columns {
+            output.push_str(&format!("{}{}", label, style.separator));
+            num_dividers += label.chars().count() + style.separator.chars().count();
+        }
+
+        output.push_str("│ Result\n");
+
+        // Print the dividers
+        for i in 0..num_dividers {
+            if i == num_dividers - 8 {
+                output.push('┼');
+            } else {
+                output.push('─');
+            }
+        }
+
+        output.push('\n');
+
+        // Print the values and results, padding each bit to the width of its column label
+        for (permutation, result) in &self.values_and_results {
+            if !filter(*permutation, *result) {
+                continue;
+            }
+
+            for (value, label) in self.values_of(*permutation).into_iter().zip(&labels) {
+                output.push_str(&format!(
+                    "{:<width$}{}",
+                    value as u8,
+                    style.separator,
+                    width = label.chars().count()
+                ));
+            }
+
+            for (label, values) in &self.subexpression_columns {
+                output.push_str(&format!(
+                    "{:<width$}{}",
+                    if values[permutation] { "T" } else { "F" },
+                    style.separator,
+                    width = label.chars().count()
+                ));
+            }
+
+            output.push_str(&format!("│      {}\n", if *result { "T" } else { "F" }));
+        }
+
+        output.push('\n');
+
+        output
+    }
+}
+
+// Parses a set of rows such as "001, 011, 101, 110" into a truth table, like try_parse_rows
+impl TryFrom<&str> for TruthTable {
+    type Error = RowParseError;
+
+    fn try_from(rows: &str) -> Result<Self, Self::Error> {
+        Self::try_parse_rows(rows)
+    }
+}
+
+// Represents one of the standard two-input logic gates
+#[derive(PartialEq, Debug)]
+pub enum GateKind {
+    And,
+    Or,
+    Xor,
+    Nand,
+    Nor,
+    Xnor,
+}
+
+// Formatting options used when printing a truth table
+pub struct TableStyle {
+    // Annotates each proposition in the header with its weight in the row's integer index, such as "A(8)"
+    pub show_weights: bool,
+    // The separator placed after each proposition column, such as a space, tab, or comma
+    pub separator: String,
+}
+
+impl Default for TableStyle {
+    fn default() -> Self {
+        Self {
+            show_weights: false,
+            separator: String::from(" "),
+        }
+    }
+}
+
+// Finds the index of the candidate expression whose truth table has the smallest Hamming distance to the target's
+// Candidates with different propositions than the target are skipped, and ties resolve to the earliest candidate
+pub fn closest_expression(target: &mut Expression, candidates: &mut [Expression]) -> Option<usize> {
+    let target = TruthTable::from_expression(target);
+
+    let mut closest: Option<(usize, usize)> = None;
+
+    for (i, candidate) in candidates.iter_mut().enumerate() {
+        let candidate = TruthTable::from_expression(candidate);
+
+        if let Some(distance) = target.hamming_distance(&candidate) {
+            if closest.is_none_or(|(_, closest_distance)| distance < closest_distance) {
+                closest = Some((i, distance));
+            }
+        }
+    }
+
+    closest.map(|(i, _)| i)
+}
+
+// Represents two truth tables which could not be compared, since they contain different propositions
+#[derive(Clone, PartialEq, Debug)]
+pub struct ComparisonError {
+    pub left: Vec<PropositionIdentifier>,
+    pub right: Vec<PropositionIdentifier>,
+}
+
+impl ComparisonError {
+    fn new(left: &TruthTable, right: &TruthTable) -> Self {
+        Self {
+            left: left.propositions.clone(),
+            right: right.propositions.clone(),
+        }
+    }
+}
+
+impl fmt::Display for ComparisonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = |propositions: &[PropositionIdentifier]| {
+            propositions
+                .iter()
+                .map(PropositionIdentifier::to_string)
+                .collect::<Vec<String>>()
+                .join(", ")
+        };
+
+        write!(
+            f,
+            "Truth tables must contain the same propositions to be compared, but they contain ({}) and ({})",
+            names(&self.left),
+            names(&self.right)
+        )
+    }
+}
+
+impl std::error::Error for ComparisonError {}
+
+// Represents a proposition which is not in a truth table
+#[derive(PartialEq, Debug)]
+pub struct MissingProposition(pub PropositionIdentifier);
+
+impl fmt::Display for MissingProposition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Proposition '{}' is not in the truth table", self.0)
+    }
+}
+
+impl std::error::Error for MissingProposition {}
+
+// Represents a reason that a set of user-inputted rows could not be parsed
+#[derive(PartialEq, Debug)]
+pub enum RowParseError {
+    InvalidCharacter { character: char, row: String },
+    InvalidRowSize(usize),
+    InconsistentRowLength,
+    PropositionCountMismatch { expected: u8, found: u8 },
+}
+
+impl fmt::Display for RowParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use RowParseError::*;
+
+        match self {
+            InvalidCharacter { character, row } => {
+                write!(f, "Invalid character '{}' found in row '{}'", character, row)
+            }
+            InvalidRowSize(_) => write!(
+                f,
+                "Row size must be between 2 and 27, representing up to 26 proposition rows and one result row"
+            ),
+            InconsistentRowLength => write!(f, "All rows must be the same length"),
+            PropositionCountMismatch { expected, found } => write!(
+                f,
+                "Expected {} propositions, but the rows contain {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RowParseError {}
+
+// Represents a reason that a list of minterms could not be parsed
+#[derive(PartialEq, Debug)]
+pub enum MintermParseError {
+    InvalidMinterm(String),
+    MintermOutOfRange { index: u32, row_count: u32 },
+    InvalidPropositionCount(u8),
+}
+
+impl fmt::Display for MintermParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use MintermParseError::*;
+
+        match self {
+            InvalidMinterm(minterm) => write!(f, "Invalid minterm '{}'", minterm),
+            MintermOutOfRange { index, row_count } => write!(
+                f,
+                "Minterm {} is out of range, the table only has rows 0 to {}",
+                index,
+                row_count - 1
+            ),
+            InvalidPropositionCount(_) => write!(
+                f,
+                "Proposition count must be between 1 and {}",
+                PropositionIdentifier::MAX_COUNT
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MintermParseError {}
+
+// Represents a disjunction which would contain more conjunctions than allowed
+#[derive(PartialEq, Debug)]
+pub struct TooManyTerms {
+    pub max_terms: usize,
+    pub terms: usize,
+}
+
+impl fmt::Display for TooManyTerms {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Expression would contain {} terms, but at most {} are allowed",
+            self.terms, self.max_terms
+        )
+    }
+}
+
+impl std::error::Error for TooManyTerms {}
+
+// Represents a row index which is not contained in a truth table
+#[derive(PartialEq, Debug)]
+pub struct RowOutOfRange {
+    pub index: u32,
+    pub row_count: usize,
+}
+
+impl fmt::Display for RowOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Row {} is out of range, the table only has rows 0 to {}",
+            self.index,
+            self.row_count - 1
+        )
+    }
+}
+
+impl std::error::Error for RowOutOfRange {}
+
+// Checks a set of rows against formatting requirements
+fn validate_rows(rows: &[&str]) -> Result<(), RowParseError> {
+    // Make sure all rows contain only '0' and '1'
+    for row in rows {
+        for c in row.chars() {
+            if c != '0' && c != '1' {
+                return Err(RowParseError::InvalidCharacter {
+                    character: c,
+                    row: row.to_string(),
+                });
+            }
+        }
+    }
+
+    // Make sure all rows are the same length, and that they are within the range of 2 to 27
+    let row_size = rows[0].len();
+
+    if !(2..=PropositionIdentifier::MAX_COUNT as usize + 1).contains(&row_size) {
+        return Err(RowParseError::InvalidRowSize(row_size));
+    }
+
+    for row in rows {
+        if row.len() != row_size {
+            return Err(RowParseError::InconsistentRowLength);
+        }
+    }
+
+    Ok(())
+}
+
+// Gets a list of propositions based on the given count
+// It is assumed that the propositions are named in alphabetical order starting from A, and will never be out of order
+pub(crate) fn get_propositions(proposition_count: u8) -> Vec<PropositionIdentifier> {
+    let mut propositions = Vec::new();
+    
+    for i in 0..proposition_count {
+        propositions.push(PropositionIdentifier::from_int(i));
+    }
+    
+    propositions
+}
+
+// Splits user-inputted rows on commas or newlines, removing any whitespace between the bits of each row
+fn split_rows(rows: &str) -> Vec<String> {
+    rows.split([',', '\n'])
+        .map(|row| row.split_whitespace().collect::<String>())
+        .filter(|row| !row.is_empty())
+        .collect()
+}
+
+// Parses a set of string-encoded rows into a map of permutations and their results
+fn rows_to_value_map(rows: &[&str]) -> Result<BTreeMap<u32, bool>, RowParseError> {
+    // Ensure the rows are valid before attempting to parse them
+    validate_rows(rows)?;
+
+    let mut values_and_results = BTreeMap::new();
+
+    for row in rows {
+        let permutation = decode_permutation_str(row);
+        let result = row.ends_with('1');
+
+        values_and_results.insert(permutation, result);
+    }
+
+    Ok(values_and_results)
+}
+
+// Takes a string-encoded row and decodes it into a value permutation
+fn decode_permutation_str(row: &str) -> u32 {
+    // Last character is the result, so it is ignored
+    let row = &row[0..row.len() - 1];
+
+    // Find the number of propositions
+    let proposition_count = row.len();
+
+    // Convert to bits and shift into a permutation (0bA/0bAB/0bABC/... -> 0bABC...Z)
+    index_to_permutation(
+        u32::from_str_radix(row, 2).unwrap(),
+        proposition_count as u8,
+    )
+}
+
+// Takes a value permutation and encodes the given propositions into a conjunction in parentheses such as "(A & B & C)"
+pub(crate) fn encode_conjunction(
+    permutation: u32,
+    propositions: &[PropositionIdentifier],
+) -> String {
+    encode_literals(permutation, propositions, " & ", false)
+}
+
+// Takes a value permutation and encodes the given propositions into a disjunction in parentheses such as "(!A | B)"
+// This is the maxterm which is false only for the given permutation
+pub(crate) fn encode_disjunction(
+    permutation: u32,
+    propositions: &[PropositionIdentifier],
+) -> String {
+    encode_literals(permutation, propositions, " | ", true)
+}
+
+// Encodes the values of the given propositions in a permutation into literals joined by an operator, in parentheses
+// Propositions whose value matches the negated value are denoted as negated
+fn encode_literals(
+    permutation: u32,
+    propositions: &[PropositionIdentifier],
+    operator: &str,
+    negated: bool,
+) -> String {
+    let literals: Vec<String> = propositions
+        .iter()
+        .enumerate()
+        .map(|(position, proposition)| {
+            if (permutation & proposition_bit(position) != 0) == negated {
+                format!("!{}", proposition)
+            } else {
+                proposition.to_string()
+            }
+        })
+        .collect();
+
+    format!("({})", literals.join(operator))
+}
+
+// Gets every ordering of the given number of positions, such as [0, 1], [1, 0]
+fn get_orderings(count: usize) -> Vec<Vec<usize>> {
+    if count == 0 {
+        return vec![Vec::new()];
+    }
+
+    let mut orderings = Vec::new();
+
+    // Insert the last position into every slot of each ordering of the other positions
+    for ordering in get_orderings(count - 1) {
+        for i in 0..count {
+            let mut ordering = ordering.clone();
+            ordering.insert(i, count - 1);
+
+            orderings.push(ordering);
+        }
+    }
+
+    orderings
+}
+
+// Advances the SplitMix64 pseudorandom number generator state, and returns the next number
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+
+    z ^ (z >> 31)
+}
+
+// Converts a conventional row index into a permutation, where the first proposition is the most significant bit
+// of the u32 (0bA/0bAB/0bABC/... -> 0bABC...Z)
+pub(crate) fn index_to_permutation(index: u32, proposition_count: u8) -> u32 {
+    index
+        .checked_shl(32 - proposition_count as u32)
+        .unwrap_or(0)
+}
+
+// Converts a permutation into its conventional row index (0bABC...Z -> 0bA/0bAB/0bABC/...)
+pub(crate) fn permutation_to_index(permutation: u32, proposition_count: u8) -> u32 {
+    permutation
+        .checked_shr(32 - proposition_count as u32)
+        .unwrap_or(0)
+}
+
+// Gets a range of numbers with all possible permutations of a given number of bits
+// Reversing the bits of each count moves it into the highest bits of the u32, so the first proposition is always
+// the most significant bit and only the first given number of propositions are ever set, whatever the count is
+pub(crate) fn get_bit_permutations(bits: u8) -> Vec<u32> {
+    // Each bit is a proposition in a u32, so a shift by 32 or more would overflow
+    assert!(bits < 32, "Cannot generate permutations of {} bits", bits);
+
+    let mut permutations = Vec::new();
+
+    for i in 0u32..(1 << bits) {
+        permutations.push(i.reverse_bits());
+    }
+
+    permutations
+}
+
+// Converts a permutation of one list of propositions into a permutation of another, where each proposition keeps its
+// value and any propositions missing from the first list are false
+pub(crate) fn convert_permutation(
+    permutation: u32,
+    from: &[PropositionIdentifier],
+    to: &[PropositionIdentifier],
+) -> u32 {
+    to.iter()
+        .enumerate()
+        .fold(0, |converted, (position, proposition)| {
+            match from.iter().position(|p| p == proposition) {
+                Some(i) if permutation & proposition_bit(i) != 0 => {
+                    converted | proposition_bit(position)
+                }
+                _ => converted,
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_disjunction() {
+        assert_eq!(encode_disjunction(0b0000, &get_propositions(1)), "(A)");
+        assert_eq!(
+            encode_disjunction(0b1000 << 28, &get_propositions(1)),
+            "(!A)"
+        );
+        assert_eq!(
+            encode_disjunction(0b0100 << 28, &get_propositions(2)),
+            "(A | !B)"
+        );
+        assert_eq!(
+            encode_disjunction(0b1010 << 28, &get_propositions(3)),
+            "(!A | B | !C)"
+        );
+        assert_eq!(
+            encode_disjunction(0b1111 << 28, &get_propositions(4)),
+            "(!A | !B | !C | !D)"
+        );
+    }
+
+    #[test]
+    fn test_get_bit_permutations() {
+        // Permutations are left-aligned in the u32 regardless of the number of bits, so A is always bit 31
+        assert_eq!(get_bit_permutations(0), vec![0b0000]);
+        assert_eq!(get_bit_permutations(1), [0b0000, 0b1000].map(|p| p << 28));
+        assert_eq!(
+            get_bit_permutations(2),
+            [0b0000, 0b1000, 0b0100, 0b1100].map(|p| p << 28)
+        );
+        assert_eq!(
+            get_bit_permutations(3),
+            [0b0000, 0b1000, 0b0100, 0b1100, 0b0010, 0b1010, 0b0110, 0b1110].map(|p| p << 28)
+        );
+        assert_eq!(
+            get_bit_permutations(4),
+            [
+                0b0000, 0b1000, 0b0100, 0b1100, 0b0010, 0b1010, 0b0110, 0b1110, 0b0001, 0b1001,
+                0b0101, 0b1101, 0b0011, 0b1011, 0b0111, 0b1111
+            ]
+            .map(|p| p << 28)
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot generate permutations of 32 bits")]
+    fn test_get_bit_permutations_overflow() {
+        get_bit_permutations(32);
+    }
+
+    #[test]
+    fn test_get_bit_permutations_self_consistent() {
+        // Every count produces each combination of its propositions exactly once, without setting any others
+        for bits in 0..=8 {
+            let permutations = get_bit_permutations(bits);
+            let mut combinations: Vec<Vec<bool>> = permutations
+                .iter()
+                .map(|permutation| {
+                    (0..bits as usize)
+                        .map(|i| permutation & proposition_bit(i) != 0)
+                        .collect()
+                })
+                .collect();
+
+            combinations.sort();
+            combinations.dedup();
+            assert_eq!(combinations.len(), 1 << bits);
+
+            let unused = !index_to_permutation((1 << bits) - 1, bits);
+            assert!(permutations
+                .iter()
+                .all(|permutation| permutation & unused == 0));
+        }
+    }
+
+    #[test]
+    fn test_decode_permutations() {
+        assert_eq!(decode_permutation_str("01"), 0b0000);
+        assert_eq!(decode_permutation_str("11"), 0b1000 << 28);
+        assert_eq!(decode_permutation_str("101"), 0b1000 << 28);
+        assert_eq!(decode_permutation_str("111"), 0b1100 << 28);
+        assert_eq!(decode_permutation_str("011"), 0b0100 << 28);
+        assert_eq!(decode_permutation_str("1001"), 0b1000 << 28);
+        assert_eq!(decode_permutation_str("1011"), 0b1010 << 28);
+        assert_eq!(decode_permutation_str("1101"), 0b1100 << 28);
+        assert_eq!(decode_permutation_str("1111"), 0b1110 << 28);
+        assert_eq!(decode_permutation_str("10001"), 0b1000 << 28);
+        assert_eq!(decode_permutation_str("10011"), 0b1001 << 28);
+        assert_eq!(decode_permutation_str("10101"), 0b1010 << 28);
+        assert_eq!(decode_permutation_str("10111"), 0b1011 << 28);
+        assert_eq!(decode_permutation_str("11001"), 0b1100 << 28);
+        assert_eq!(decode_permutation_str("11011"), 0b1101 << 28);
+        assert_eq!(decode_permutation_str("11101"), 0b1110 << 28);
+        assert_eq!(decode_permutation_str("11111"), 0b1111 << 28);
+    }
+
+    #[test]
+    fn test_encode_conjunction() {
+        assert_eq!(encode_conjunction(0b0000, &get_propositions(1)), "(!A)");
+        assert_eq!(
+            encode_conjunction(0b1000 << 28, &get_propositions(1)),
+            "(A)"
+        );
+        assert_eq!(
+            encode_conjunction(0b0100 << 28, &get_propositions(2)),
+            "(!A & B)"
+        );
+        assert_eq!(
+            encode_conjunction(0b1100 << 28, &get_propositions(2)),
+            "(A & B)"
+        );
+        assert_eq!(
+            encode_conjunction(0b0010 << 28, &get_propositions(3)),
+            "(!A & !B & C)"
+        );
+        assert_eq!(
+            encode_conjunction(0b1010 << 28, &get_propositions(3)),
+            "(A & !B & C)"
+        );
+        assert_eq!(
+            encode_conjunction(0b0110 << 28, &get_propositions(3)),
+            "(!A & B & C)"
+        );
+        assert_eq!(
+            encode_conjunction(0b1110 << 28, &get_propositions(3)),
+            "(A & B & C)"
+        );
+        assert_eq!(
+            encode_conjunction(0b0001 << 28, &get_propositions(4)),
+            "(!A & !B & !C & D)"
+        );
+        assert_eq!(
+            encode_conjunction(0b1001 << 28, &get_propositions(4)),
+            "(A & !B & !C & D)"
+        );
+        assert_eq!(
+            encode_conjunction(0b0101 << 28, &get_propositions(4)),
+            "(!A & B & !C & D)"
+        );
+        assert_eq!(
+            encode_conjunction(0b1101 << 28, &get_propositions(4)),
+            "(A & B & !C & D)"
+        );
+        assert_eq!(
+            encode_conjunction(0b0011 << 28, &get_propositions(4)),
+            "(!A & !B & C & D)"
+        );
+        assert_eq!(
+            encode_conjunction(0b1011 << 28, &get_propositions(4)),
+            "(A & !B & C & D)"
+        );
+        assert_eq!(
+            encode_conjunction(0b0111 << 28, &get_propositions(4)),
+            "(!A & B & C & D)"
+        );
+        assert_eq!(
+            encode_conjunction(0b1111 << 28, &get_propositions(4)),
+            "(A & B & C & D)"
+        );
+    }
+
+    #[test]
+    fn test_to_disjunction() {
+        let table = TruthTable::parse_expression_str("A & B & C & D");
+        assert_eq!(table.to_disjunction(), "(A & B & C & D)");
+
+        let table = TruthTable::parse_expression_str("A & B & C & !D");
+        assert_eq!(table.to_disjunction(), "(A & B & C & !D)");
+
+        let table = TruthTable::parse_expression_str("A & B & !C & D");
+        assert_eq!(table.to_disjunction(), "(A & B & !C & D)");
+
+        let table = TruthTable::parse_expression_str("A & B & !C & !D");
+        assert_eq!(table.to_disjunction(), "(A & B & !C & !D)");
+
+        let table = TruthTable::parse_expression_str("A & !B & C & D");
+        assert_eq!(table.to_disjunction(), "(A & !B & C & D)");
+
+        let table = TruthTable::parse_expression_str("(A & B) | C");
+        assert_eq!(table.to_disjunction(), "(!A & !B & C) | (!A & B & C) | (A & !B & C) | (A & B & !C) | (A & B & C)");
+    }
+
+    #[test]
+    fn test_styled_header_weights() {
+        let table = TruthTable::parse_expression_str("A & B & C & D");
+
+        let style = TableStyle {
+            show_weights: true,
+            ..Default::default()
+        };
+        let output = table.to_styled_string(&style);
+        let mut lines = output.lines();
+
+        assert_eq!(lines.next(), Some("A(8) B(4) C(2) D(1) │ Result"));
+        assert_eq!(lines.next(), Some("────────────────────┼───────"));
+        assert_eq!(lines.next(), Some("0    0    0    0    │      F"));
+    }
+
+    #[test]
+    fn test_styled_default_matches_print() {
+        let table = TruthTable::parse_expression_str("A | B");
+        let output = table.to_styled_string(&TableStyle::default());
+
+        assert_eq!(
+            output,
+            "A B │ Result\n────┼───────\n0 0 │      F\n0 1 │      T\n1 0 │      T\n1 1 │      T\n\n"
+        );
+    }
+
+    #[test]
+    fn test_identify_gate() {
+        let table = TruthTable::parse_expression_str("A & B");
+        assert_eq!(table.identify_gate(), Some(GateKind::And));
+
+        let table = TruthTable::parse_expression_str("!(A | B)");
+        assert_eq!(table.identify_gate(), Some(GateKind::Nor));
+
+        let table = TruthTable::parse_expression_str("A ^ B");
+        assert_eq!(table.identify_gate(), Some(GateKind::Xor));
+
+        let table = TruthTable::parse_expression_str("A ~^ B");
+        assert_eq!(table.identify_gate(), Some(GateKind::Xnor));
+
+        let table = TruthTable::parse_expression_str("A & !B");
+        assert_eq!(table.identify_gate(), None);
+
+        let table = TruthTable::parse_expression_str("A & B & C");
+        assert_eq!(table.identify_gate(), None);
+    }
+
+    #[test]
+    fn test_venn_regions() {
+        let table = TruthTable::parse_expression_str("A | B");
+        assert_eq!(table.venn_regions(), vec!["B only", "A only", "A∩B"]);
+
+        let table = TruthTable::parse_expression_str("!A & !B");
+        assert_eq!(table.venn_regions(), vec!["neither"]);
+
+        let table = TruthTable::parse_expression_str("A & B & !C");
+        assert_eq!(table.venn_regions(), vec!["A∩B only"]);
+    }
+
+    #[test]
+    fn test_styled_separator() {
+        let table = TruthTable::parse_expression_str("A & B");
+
+        let style = TableStyle {
+            separator: String::from("\t"),
+            ..Default::default()
+        };
+        let output = table.to_styled_string(&style);
+        let lines: Vec<&str> = output.lines().collect();
+
+        assert_eq!(lines[0], "A\tB\t│ Result");
+        assert_eq!(lines[2], "0\t0\t│      F");
+        assert_eq!(lines[5], "1\t1\t│      T");
+    }
+
+    #[test]
+    fn test_sensitivity_at() {
+        let table = TruthTable::parse_expression_str("A ^ B ^ C");
+
+        for permutation in get_bit_permutations(3) {
+            assert_eq!(table.sensitivity_at(permutation), Some(3));
+        }
+
+        let table = TruthTable::parse_expression_str("A & B");
+        assert_eq!(table.sensitivity_at(0b0000), Some(0));
+        assert_eq!(table.sensitivity_at(0b1000 << 28), Some(1));
+        assert_eq!(table.sensitivity_at(0b1100 << 28), Some(2));
+
+        // Permutations outside of the table have no sensitivity
+        assert_eq!(table.sensitivity_at(0b0010 << 28), None);
+
+        // Flipping A leads to a missing row, so only the flip of B is counted
+        let table = TruthTable::parse_rows("000, 011");
+        assert_eq!(table.sensitivity_at(0), Some(1));
+    }
+
+    #[test]
+    fn test_parse_minterms() {
+        let expected = TruthTable::parse_rows("0000, 0011, 0100, 0111, 1000, 1011, 1100, 1111");
+
+        assert_eq!(TruthTable::parse_minterms("Σm(1, 3, 5, 7)", 3), expected);
+        assert_eq!(TruthTable::parse_minterms("m(1,3,5,7)", 3), expected);
+        assert_eq!(TruthTable::parse_minterms(" 7, 5,3 ,1 ", 3), expected);
+        assert_eq!(
+            TruthTable::parse_minterms("Σm(1,3,5,7)", 3).to_minterm_notation(),
+            "f(A,B,C) = Σm(1,3,5,7)"
+        );
+
+        // An empty list is a contradiction
+        assert_eq!(
+            TruthTable::parse_minterms("Σm()", 2).to_minterm_notation(),
+            "f(A,B) = Σm()"
+        );
+
+        assert_eq!(
+            TruthTable::try_parse_minterms("1, x", 2),
+            Err(MintermParseError::InvalidMinterm(String::from("x")))
+        );
+        assert_eq!(
+            TruthTable::try_parse_minterms("1, 4", 2),
+            Err(MintermParseError::MintermOutOfRange {
+                index: 4,
+                row_count: 4
+            })
+        );
+        assert_eq!(
+            TruthTable::try_parse_minterms("1", 0),
+            Err(MintermParseError::InvalidPropositionCount(0))
+        );
+    }
+
+    #[test]
+    fn test_to_minterm_notation() {
+        let table = TruthTable::parse_expression_str("A & B");
+        assert_eq!(table.to_minterm_notation(), "f(A,B) = Σm(3)");
+
+        let table = TruthTable::parse_expression_str("(!A & C) | (B & C)");
+        assert_eq!(table.to_minterm_notation(), "f(A,B,C) = Σm(1,3,7)");
+
+        let table = TruthTable::parse_expression_str("A & !A");
+        assert_eq!(table.to_minterm_notation(), "f(A) = Σm()");
+    }
+
+    #[test]
+    fn test_to_maxterm_notation() {
+        let table = TruthTable::parse_expression_str("A | B");
+        assert_eq!(table.to_maxterm_notation(), "f(A,B) = ΠM(0)");
+
+        let table = TruthTable::parse_expression_str("(!A & C) | (B & C)");
+        assert_eq!(table.to_maxterm_notation(), "f(A,B,C) = ΠM(0,2,4,5,6)");
+    }
+
+    #[test]
+    fn test_hamming_distance() {
+        let a = TruthTable::parse_expression_str("A & B");
+        let b = TruthTable::parse_expression_str("A | B");
+        let c = TruthTable::parse_expression_str("A & B & C");
+
+        assert_eq!(a.hamming_distance(&a), Some(0));
+        assert_eq!(a.hamming_distance(&b), Some(2));
+        assert_eq!(a.hamming_distance(&c), None);
+    }
+
+    #[test]
+    fn test_closest_expression() {
+        let mut target = Expression::parse("A & B", true);
+        let mut candidates = vec![
+            Expression::parse("!A & !B", true),
+            Expression::parse("A & B & C", true),
+            Expression::parse("A", true),
+            Expression::parse("(A & B) | (!A & B)", true),
+            Expression::parse("A | B", true),
+        ];
+
+        assert_eq!(closest_expression(&mut target, &mut candidates), Some(3));
+
+        let mut candidates = vec![Expression::parse("A & B & C", true)];
+        assert_eq!(closest_expression(&mut target, &mut candidates), None);
+    }
+
+    #[test]
+    fn test_parse_rows_with_count() {
+        let table = TruthTable::parse_rows_with_count("001, 011, 101, 110", 2);
+        assert_eq!(table, TruthTable::parse_rows("001, 011, 101, 110"));
+    }
+
+    #[test]
+    #[should_panic(expected = "Expected 3 propositions, but the rows contain 2")]
+    fn test_parse_rows_with_count_mismatch() {
+        TruthTable::parse_rows_with_count("001, 011, 101, 110", 3);
+    }
+
+    #[test]
+    fn test_walsh_coefficients() {
+        let table = TruthTable::parse_expression_str("A ^ B");
+        assert_eq!(table.walsh_coefficients(), vec![0, 0, 0, 4]);
+
+        let table = TruthTable::parse_expression_str("A & B");
+        assert_eq!(table.walsh_coefficients(), vec![2, 2, 2, -2]);
+
+        let table = TruthTable::parse_expression_str("A | !A");
+        assert_eq!(table.walsh_coefficients(), vec![-2, 0]);
+    }
+
+    #[test]
+    fn test_to_comparison_string() {
+        let left = TruthTable::parse_expression_str("A & B");
+        let right = TruthTable::parse_expression_str("A | B");
+
+        assert_eq!(
+            left.to_comparison_string(&right),
+            Ok(String::from(
+                "A B │ Left Right Diff\n\
+                 ────┼────────────────\n\
+                 0 0 │    F     F\n\
+                 0 1 │    F     T    *\n\
+                 1 0 │    F     T    *\n\
+                 1 1 │    T     T\n\n"
+            ))
+        );
+
+        // Tables over different propositions cannot be compared
+        let other = TruthTable::parse_expression_str_with_gaps("A | C");
+        let error = left.to_comparison_string(&other).unwrap_err();
+
+        assert_eq!(left.print_comparison(&other), Err(error.clone()));
+        assert_eq!(
+            error.to_string(),
+            "Truth tables must contain the same propositions to be compared, but they contain (A, B) and (A, C)"
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let forward = TruthTable::parse_expression_str("A -> B");
+        let backward = TruthTable::parse_expression_str("B -> A");
+
+        assert_eq!(
+            forward.diff(&backward),
+            Ok(vec![(0b01 << 30, true, false), (0b10 << 30, false, true)])
+        );
+        assert_eq!(forward.diff(&forward), Ok(Vec::new()));
+
+        assert_eq!(
+            forward.to_diff_string(&backward),
+            Ok(String::from(
+                "A B │ Left Right\n\
+                 ────┼───────────\n\
+                 0 1 │    T     F\n\
+                 1 0 │    F     T\n\n"
+            ))
+        );
+        assert_eq!(
+            forward.to_diff_string(&forward),
+            Ok(String::from(
+                "A B │ Left Right\n\
+                 ────┼───────────\n\
+                 No rows differ\n\n"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_diff_mismatch() {
+        let a = TruthTable::parse_expression_str("A & B");
+        let b = TruthTable::parse_expression_str("A & B & C");
+        let error = a.diff(&b).unwrap_err();
+
+        assert_eq!(a.to_diff_string(&b), Err(error.clone()));
+        assert_eq!(a.print_diff(&b), Err(error.clone()));
+        assert_eq!(
+            error.to_string(),
+            "Truth tables must contain the same propositions to be compared, but they contain (A, B) and (A, B, C)"
+        );
+    }
+
+    #[test]
+    fn test_support() {
+        let [a, b, c] = ['A', 'B', 'C'].map(PropositionIdentifier::from_char);
+
+        let table = TruthTable::parse_expression_str("(A & B) | (A & !B)");
+        assert_eq!(table.support(), vec![a]);
+
+        let table = TruthTable::parse_expression_str("(A | !A) & (B | C)");
+        assert_eq!(table.support(), vec![b, c]);
+
+        let table = TruthTable::parse_expression_str("A | !A");
+        assert!(table.support().is_empty());
+    }
+
+    #[test]
+    fn test_to_verilog() {
+        let table = TruthTable::parse_expression_str("A & B");
+        assert_eq!(table.to_verilog("f"), "assign f = (A & B);");
+
+        let table = TruthTable::parse_expression_str("A & !B");
+        assert_eq!(table.to_verilog("out"), "assign out = (A & ~B);");
+
+        let table = TruthTable::parse_expression_str("A | !A");
+        assert_eq!(table.to_verilog("f"), "assign f = 1'b1;");
+
+        let table = TruthTable::parse_expression_str("A & !A");
+        assert_eq!(table.to_verilog("f"), "assign f = 1'b0;");
+    }
+
+    #[test]
+    fn test_try_to_row_string() {
+        let table = TruthTable::parse_expression_str("A & B");
+
+        assert_eq!(
+            table.try_to_row_string(3),
+            Ok(String::from("A B │ Result\n────┼───────\n1 1 │      T\n\n"))
+        );
+        assert_eq!(
+            table.try_to_row_string(9),
+            Err(RowOutOfRange {
+                index: 9,
+                row_count: 4
+            })
+        );
+        assert_eq!(
+            table.try_to_row_string(9).unwrap_err().to_string(),
+            "Row 9 is out of range, the table only has rows 0 to 3"
+        );
+    }
+
+    #[test]
+    fn test_rows_by_index() {
+        let table = TruthTable::parse_expression_str("A & !B");
+        let rows: Vec<(u32, Vec<bool>, bool)> = table.rows_by_index().collect();
+
+        assert_eq!(
+            rows,
+            vec![
+                (0, vec![false, false], false),
+                (1, vec![false, true], false),
+                (2, vec![true, false], true),
+                (3, vec![true, true], false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_symmetric() {
+        let table = TruthTable::parse_expression_str("A ^ B ^ C");
+        assert!(table.is_symmetric());
+
+        let table = TruthTable::parse_expression_str("(A & B) | (A & C) | (B & C)");
+        assert!(table.is_symmetric());
+
+        let table = TruthTable::parse_expression_str("A & !B");
+        assert!(!table.is_symmetric());
+    }
+
+    #[test]
+    fn test_symmetric_spec() {
+        let table = TruthTable::parse_expression_str("(A & B) | (A & C) | (B & C)");
+        assert_eq!(table.symmetric_spec(), Some(vec![false, false, true, true]));
+
+        let table = TruthTable::parse_expression_str("!A & !B");
+        assert_eq!(table.symmetric_spec(), Some(vec![true, false, false]));
+
+        let table = TruthTable::parse_expression_str("A & !B");
+        assert_eq!(table.symmetric_spec(), None);
+    }
+
+    #[test]
+    fn test_random() {
+        assert_eq!(TruthTable::random(3, 42), TruthTable::random(3, 42));
+        assert_eq!(TruthTable::random(4, 7).values_and_results.len(), 16);
+
+        // Different seeds should produce at least some different functions
+        let tables: Vec<TruthTable> = (0..8).map(|seed| TruthTable::random(4, seed)).collect();
+        assert!(tables.iter().any(|table| *table != tables[0]));
+    }
+
+    #[test]
+    fn test_to_row_string() {
+        let table = TruthTable::parse_expression_str("A & B");
+
+        assert_eq!(
+            table.to_row_string(3),
+            "A B │ Result\n────┼───────\n1 1 │      T\n\n"
+        );
+        assert_eq!(
+            table.to_row_string(1),
+            "A B │ Result\n────┼───────\n0 1 │      F\n\n"
+        );
+    }
+
+    #[test]
+    fn test_to_complement_disjunction() {
+        let table = TruthTable::parse_expression_str("A | B");
+        assert_eq!(table.to_complement_disjunction(), "(!A & !B)");
+
+        let complement = TruthTable::parse_expression_str(&table.to_complement_disjunction());
+        assert_eq!(complement, TruthTable::parse_expression_str("!(A | B)"));
+
+        let table = TruthTable::parse_expression_str("A ^ B");
+        assert_eq!(table.to_complement_disjunction(), "(!A & !B) | (A & B)");
+    }
+
+    #[test]
+    fn test_parse_rows_delimiters() {
+        let expected = TruthTable::parse_rows("001, 011, 101, 110");
+
+        assert_eq!(TruthTable::parse_rows("001\n011\n101\n110\n"), expected);
+        assert_eq!(
+            TruthTable::parse_rows("0 0 1, 0 1 1, 1 0 1, 1 1 0"),
+            expected
+        );
+        assert_eq!(
+            TruthTable::parse_rows("0 0 1\r\n0 1 1\r\n1 0 1\r\n1 1 0"),
+            expected
+        );
+        assert_eq!(TruthTable::parse_rows("001,011,\n101,  110"), expected);
+    }
+
+    #[test]
+    fn test_try_parse_rows_errors() {
+        assert!(TruthTable::try_parse_rows("001, 011, 101, 110").is_ok());
+        assert_eq!(
+            TruthTable::try_parse_rows("001, 0x1"),
+            Err(RowParseError::InvalidCharacter {
+                character: 'x',
+                row: String::from("0x1")
+            })
+        );
+        assert_eq!(
+            TruthTable::try_parse_rows("1, 0"),
+            Err(RowParseError::InvalidRowSize(1))
+        );
+        assert_eq!(
+            TruthTable::try_parse_rows(&"0".repeat(28)),
+            Err(RowParseError::InvalidRowSize(28))
+        );
+        assert_eq!(
+            TruthTable::try_parse_rows("001, 0111"),
+            Err(RowParseError::InconsistentRowLength)
+        );
+        assert_eq!(
+            TruthTable::try_parse_rows("0 0 1\n0 1 1 1"),
+            Err(RowParseError::InconsistentRowLength)
+        );
+        assert_eq!(
+            TruthTable::try_parse_rows(" \n"),
+            Err(RowParseError::InvalidRowSize(0))
+        );
+        assert_eq!(
+            TruthTable::try_parse_rows_with_count("001, 011", 3),
+            Err(RowParseError::PropositionCountMismatch {
+                expected: 3,
+                found: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_to_minimized_dnf() {
+        let table = TruthTable::parse_expression_str("(A & B) | (A & !B)");
+        assert_eq!(table.to_minimized_dnf(), "A");
+
+        let table = TruthTable::parse_expression_str("A & B & C & D");
+        assert_eq!(table.to_minimized_dnf(), "(A & B & C & D)");
+
+        // Constant tables minimize to constants
+        let table = TruthTable::parse_expression_str("A | !A");
+        assert_eq!(table.to_minimized_dnf(), "1");
+
+        let table = TruthTable::parse_expression_str("A & !A");
+        assert_eq!(table.to_minimized_dnf(), "0");
+
+        let table = TruthTable::parse_expression_str_with_gaps("1");
+        assert_eq!(table.to_minimized_dnf(), "1");
+
+        let table = TruthTable::parse_expression_str_with_gaps("0 & 1");
+        assert_eq!(table.to_minimized_dnf(), "0");
+    }
+
+    #[test]
+    fn test_to_minimized_dnf_cyclic() {
+        // f(A,B,C) = Σm(0,1,2,5,6,7) has a cyclic prime implicant chart, so a greedy cover can use four terms
+        let mut expression = Expression::from_truth_integer(0b1110_0111, 3);
+        let table = TruthTable::from_expression(&mut expression);
+        let minimized = table.to_minimized_dnf();
+
+        assert_eq!(minimized.matches('|').count(), 2);
+        assert_eq!(
+            minimized.matches(|c: char| c.is_ascii_uppercase()).count(),
+            6
+        );
+        assert_eq!(TruthTable::parse_expression_str(&minimized), table);
+    }
+
+    #[test]
+    fn test_with_subexpression_column() {
+        let mut expression = Expression::parse("(A & B) | C", true);
+        let mut table = TruthTable::from_expression(&mut expression);
+        assert_eq!(
+            table.with_subexpression_column(&mut expression, &[0]),
+            Ok(())
+        );
+
+        // Paths which do not lead to a subexpression do not add a column
+        assert!(table
+            .with_subexpression_column(&mut expression, &[2])
+            .is_err());
+
+        assert_eq!(
+            table.to_styled_string(&TableStyle::default()),
+            "A B C (A & B) │ Result\n\
+             ──────────────┼───────\n\
+             0 0 0 F       │      F\n\
+             0 0 1 F       │      T\n\
+             0 1 0 F       │      F\n\
+             0 1 1 F       │      T\n\
+             1 0 0 F       │      F\n\
+             1 0 1 F       │      T\n\
+             1 1 0 T       │      T\n\
+             1 1 1 T       │      T\n\n"
+        );
+    }
+
+    #[test]
+    fn test_is_unate_in() {
+        let [a, b, c] = ['A', 'B', 'C'].map(PropositionIdentifier::from_char);
+
+        let table = TruthTable::parse_expression_str("A & B");
+        assert_eq!(table.is_unate_in(a.clone()), Ok(Some(true)));
+
+        let table = TruthTable::parse_expression_str("!A & B");
+        assert_eq!(table.is_unate_in(a.clone()), Ok(Some(false)));
+        assert_eq!(table.is_unate_in(b), Ok(Some(true)));
+
+        let table = TruthTable::parse_expression_str("A ^ B");
+        assert_eq!(table.is_unate_in(a), Ok(None));
+
+        // Propositions outside of the table are reported instead of panicking
+        assert_eq!(
+            table.is_unate_in(c.clone()),
+            Err(MissingProposition(c.clone()))
+        );
+        assert_eq!(
+            MissingProposition(c).to_string(),
+            "Proposition 'C' is not in the truth table"
+        );
+    }
+
+    #[test]
+    fn test_more_than_four_propositions() {
+        let table = TruthTable::parse_expression_str("A & B & C & D & E & F & G & H");
+        assert_eq!(table.values_and_results.len(), 256);
+        assert_eq!(table.to_minterm_notation(), "f(A,B,C,D,E,F,G,H) = Σm(255)");
+        assert_eq!(table.to_minimized_dnf(), "(A & B & C & D & E & F & G & H)");
+
+        let table = TruthTable::parse_rows("000000, 000011, 111110, 111111");
+        assert_eq!(table.propositions.len(), 5);
+        assert_eq!(
+            table.to_disjunction(),
+            "(!A & !B & !C & !D & E) | (A & B & C & D & E)"
+        );
+
+        assert_eq!(TruthTable::random(6, 1).values_and_results.len(), 64);
+    }
+
+    #[test]
+    fn test_satisfiability() {
+        let table = TruthTable::parse_expression_str("A | !A");
+        assert!(table.is_tautology());
+        assert!(!table.is_contradiction());
+        assert!(table.is_satisfiable());
+
+        let table = TruthTable::parse_expression_str("A & !A");
+        assert!(!table.is_tautology());
+        assert!(table.is_contradiction());
+        assert!(!table.is_satisfiable());
+        assert!(table.satisfying_assignments().is_empty());
+
+        let table = TruthTable::parse_expression_str("A & B");
+        assert!(!table.is_tautology());
+        assert!(!table.is_contradiction());
+        assert!(table.is_satisfiable());
+        assert_eq!(table.satisfying_assignments(), vec![0b1100 << 28]);
+    }
+
+    #[test]
+    fn test_all_functions() {
+        let functions: Vec<TruthTable> = TruthTable::all_functions(2).collect();
+        assert_eq!(functions.len(), 16);
+
+        assert!(functions[0].is_contradiction());
+        assert!(functions[15].is_tautology());
+        assert_eq!(functions[8], TruthTable::parse_expression_str("A & B"));
+
+        // Every function is distinct
+        for (i, a) in functions.iter().enumerate() {
+            for b in &functions[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+
+        assert_eq!(TruthTable::all_functions(1).count(), 4);
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let table = TruthTable::parse_expression_str("A & !B");
+        assert_eq!(table.to_csv(), "A,B,Result\n0,0,0\n0,1,0\n1,0,1\n1,1,0\n");
+    }
+
+    #[test]
+    fn test_to_json() {
+        let table = TruthTable::parse_expression_str("A | B");
+        assert_eq!(
+            table.to_json(),
+            "[\n  {\"A\": false, \"B\": false, \"Result\": false},\n  {\"A\": false, \"B\": true, \"Result\": true},\n  {\"A\": true, \"B\": false, \"Result\": true},\n  {\"A\": true, \"B\": true, \"Result\": true}\n]\n"
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_write_jsonl() {
+        let table = TruthTable::parse_expression_str("A & !B");
+
+        let mut output = Vec::new();
+        table.write_jsonl(&mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(output.lines().count(), table.values_and_results.len());
+        assert_eq!(
+            output.lines().nth(2),
+            Some("{\"A\":1,\"B\":0,\"result\":1}")
+        );
+    }
+
+    #[test]
+    fn test_to_conjunction() {
+        let table = TruthTable::parse_expression_str("A | B");
+        assert_eq!(table.to_disjunction(), "(!A & B) | (A & !B) | (A & B)");
+        assert_eq!(table.to_conjunction(), "(A | B)");
+
+        let table = TruthTable::parse_expression_str("A ^ B");
+        assert_eq!(table.to_conjunction(), "(A | B) & (!A | !B)");
+
+        // The sum of products and product of sums describe the same function
+        let table = TruthTable::parse_expression_str("(A -> B) & (B | C)");
+        let mut sop = Expression::parse(&table.to_disjunction(), true);
+        let mut pos = Expression::parse(&table.to_conjunction(), true);
+        assert!(sop.is_equivalent_to(&mut pos));
+
+        // Degenerate tables have an empty expression of one form
+        assert_eq!(
+            TruthTable::parse_expression_str("A | !A").to_conjunction(),
+            ""
+        );
+        assert_eq!(
+            TruthTable::parse_expression_str("A & !A").to_disjunction(),
+            ""
+        );
+    }
+
+    #[test]
+    fn test_to_minimized_dnf_textbook() {
+        // f(A,B,C,D) = Σm(0,1,2,5,6,7,8,9,10,14)
+        let table = TruthTable::parse_rows(
+            "00001, 00011, 00101, 00110, 01000, 01011, 01101, 01111, \
+             10001, 10011, 10101, 10110, 11000, 11010, 11101, 11110",
+        );
+        let minimized = table.to_minimized_dnf();
+        assert_eq!(minimized, "(!B & !C) | (C & !D) | (!A & B & D)");
+        assert_eq!(TruthTable::parse_expression_str(&minimized), table);
+
+        // f(A,B,C,D) = Σm(4,8,10,11,12,15) + d(9,14)
+        let table = TruthTable::parse_rows(
+            "00000, 00010, 00100, 00110, 01001, 01010, 01100, 01110, \
+             10001, 10010, 10101, 10111, 11001, 11010, 11100, 11111",
+        );
+        assert_eq!(
+            table.to_minimized_dnf_with_dont_cares(&[9, 14]),
+            "(B & !C & !D) | (A & !B) | (A & C)"
+        );
+
+        // Without the don't-cares, rows 9 and 14 must stay false
+        assert_eq!(
+            table.to_minimized_dnf(),
+            "(B & !C & !D) | (A & !B & !D) | (A & C & D)"
+        );
+    }
+
+    #[test]
+    fn test_to_org() {
+        let table = TruthTable::parse_expression_str("A -> B");
+        assert_eq!(
+            table.to_org(),
+            "| A | B | Result |\n\
+             |---+---+--------|\n\
+             | 0 | 0 | T      |\n\
+             | 0 | 1 | T      |\n\
+             | 1 | 0 | F      |\n\
+             | 1 | 1 | T      |\n"
+        );
+    }
+
+    #[test]
+    fn test_try_to_disjunction() {
+        // A | B | C is true in 7 of its 8 rows
+        let table = TruthTable::parse_expression_str("A | B | C");
+        assert_eq!(
+            table.try_to_disjunction(3),
+            Err(TooManyTerms {
+                max_terms: 3,
+                terms: 7
+            })
+        );
+        assert_eq!(table.try_to_disjunction(7), Ok(table.to_disjunction()));
+
+        let table = TruthTable::parse_expression_str("A & B");
+        assert_eq!(table.try_to_disjunction(1), Ok(String::from("(A & B)")));
+    }
+
+    #[test]
+    fn test_parse_expression_str_verbose() {
+        let table = TruthTable::parse_expression_str_verbose("((A & B) | C) & B");
+
+        assert_eq!(
+            table.to_styled_string(&TableStyle::default()),
+            "A B C (A & B) (A & B | C) │ Result\n\
+             ──────────────────────────┼───────\n\
+             0 0 0 F       F           │      F\n\
+             0 0 1 F       T           │      F\n\
+             0 1 0 F       F           │      F\n\
+             0 1 1 F       T           │      T\n\
+             1 0 0 F       F           │      F\n\
+             1 0 1 F       T           │      F\n\
+             1 1 0 T       T           │      T\n\
+             1 1 1 T       T           │      T\n\n"
+        );
+    }
+
+    #[test]
+    fn test_to_standard_keyed_map() {
+        let table = TruthTable::parse_expression_str("A & !B");
+        let map = table.to_standard_keyed_map();
+
+        assert_eq!(map.keys().copied().collect::<Vec<u32>>(), vec![0, 1, 2, 3]);
+        assert_eq!(
+            map.values().copied().collect::<Vec<bool>>(),
+            vec![false, false, true, false]
+        );
+    }
+
+    #[test]
+    fn test_canonical_under_permutation() {
+        let signature =
+            |expression| TruthTable::parse_expression_str(expression).canonical_under_permutation();
+
+        assert_eq!(signature("A & B & (C | !C)"), signature("A & C & (B | !B)"));
+        assert_eq!(signature("A & B & (C | !C)"), signature("B & C & (A | !A)"));
+        assert_eq!(signature("A & !B"), signature("!A & B"));
+        assert_ne!(signature("A & !B"), signature("A & B"));
+
+        assert_eq!(get_orderings(3).len(), 6);
+    }
+
+    #[test]
+    fn test_truth_ratio() {
+        let table = TruthTable::parse_expression_str("A | !A");
+        assert_eq!(table.true_count(), 2);
+        assert_eq!(table.false_count(), 0);
+        assert_eq!(table.truth_ratio(), 1.0);
+
+        let table = TruthTable::parse_expression_str("A & !A");
+        assert_eq!(table.true_count(), 0);
+        assert_eq!(table.truth_ratio(), 0.0);
+
+        let table = TruthTable::parse_expression_str("A & B");
+        assert_eq!(table.true_count(), 1);
+        assert_eq!(table.false_count(), 3);
+        assert_eq!(table.truth_ratio(), 0.25);
+    }
+
+    #[test]
+    fn test_is_anti_self_dual() {
+        assert!(TruthTable::parse_expression_str("A ^ B").is_anti_self_dual());
+        assert!(TruthTable::parse_expression_str("(A <-> B) & (B <-> C)").is_anti_self_dual());
+        assert!(!TruthTable::parse_expression_str("A & B").is_anti_self_dual());
+        assert!(!TruthTable::parse_expression_str("A").is_anti_self_dual());
+    }
+
+    #[test]
+    fn test_parse_expression_str_with_gaps() {
+        let table = TruthTable::parse_expression_str_with_gaps("A & C");
+        assert_eq!(table.propositions.len(), 2);
+        assert_eq!(table.values_and_results.len(), 4);
+        assert_eq!(table.to_minterm_notation(), "f(A,C) = Σm(3)");
+        assert_eq!(table.to_disjunction(), "(A & C)");
+        assert_eq!(table.to_conjunction(), "(A | C) & (A | !C) & (!A | C)");
+        assert_eq!(table.to_minimized_dnf(), "(A & C)");
+
+        assert_eq!(
+            table.to_styled_string(&TableStyle::default()),
+            "A C │ Result\n\
+             ────┼───────\n\
+             0 0 │      F\n\
+             0 1 │      F\n\
+             1 0 │      F\n\
+             1 1 │      T\n\n"
+        );
+
+        assert!(TruthTable::parse_expression_str_with_gaps("B ^ D").is_anti_self_dual());
+    }
+
+    #[test]
+    fn test_parse_expression_str_with_gaps_keeps_labels() {
+        let table = TruthTable::parse_expression_str_with_gaps("B & D");
+        let [b, d] = ['B', 'D'].map(PropositionIdentifier::from_char);
+
+        assert_eq!(table.propositions(), &[b, d]);
+        assert_eq!(table.true_count(), 1);
+        // Permutations are over the columns of the table, so B and D take the two highest bits
+        assert_eq!(table.satisfying_assignments(), vec![0b11 << 30]);
+
+        assert_eq!(
+            table.to_styled_string(&TableStyle::default()),
+            "B D │ Result\n\
+             ────┼───────\n\
+             0 0 │      F\n\
+             0 1 │      F\n\
+             1 0 │      F\n\
+             1 1 │      T\n\n"
+        );
+    }
+
+    #[test]
+    fn test_sixteen_propositions() {
+        let expression = ('A'..='P')
+            .map(String::from)
+            .collect::<Vec<String>>()
+            .join(" & ");
+        let table = TruthTable::parse_expression_str(&expression);
+
+        assert_eq!(table.propositions().len(), 16);
+        assert_eq!(table.rows().count(), 1 << 16);
+        assert_eq!(table.satisfying_assignments(), vec![0xFFFF << 16]);
+
+        let output = table.to_styled_string(&TableStyle::default());
+        assert_eq!(output.lines().count(), (1 << 16) + 3);
+        assert!(output.starts_with("A B C D E F G H I J K L M N O P │ Result\n"));
+        assert!(output.ends_with("1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 │      T\n\n"));
+    }
+
+    #[test]
+    fn test_on_off_sets() {
+        let table = TruthTable::parse_expression_str("A & B");
+        let (on_set, off_set) = table.on_off_sets();
+
+        assert_eq!(on_set, vec![3]);
+        assert_eq!(off_set, vec![0, 1, 2]);
+
+        // The sets partition every index
+        let mut indices = [on_set, off_set].concat();
+        indices.sort();
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+    }
+
+    #[test]
+    fn test_proposition_names() {
+        let table = TruthTable::parse_expression_str("rain -> p1");
+
+        assert_eq!(
+            table.propositions(),
+            ["p1", "rain"].map(PropositionIdentifier::from_name)
+        );
+        assert_eq!(table.to_minterm_notation(), "f(p1,rain) = Σm(0,2,3)");
+        assert_eq!(table.to_minimized_dnf(), "!rain | p1");
+
+        // Each column is as wide as its proposition's name
+        assert_eq!(
+            table.to_filtered_string(false),
+            "p1 rain │ Result\n────────┼───────\n0  1    │      F\n\n"
+        );
+        assert_eq!(
+            table.to_org(),
+            "| p1 | rain | Result |\n|----+------+--------|\n\
+             | 0  | 0    | T      |\n\
+             | 0  | 1    | F      |\n\
+             | 1  | 0    | T      |\n\
+             | 1  | 1    | T      |\n"
+        );
+        assert_eq!(
+            table
+                .to_diff_string(&TruthTable::parse_expression_str("p1 -> rain"))
+                .unwrap(),
+            "p1 rain │ Left Right\n────────┼───────────\n0  1    │    F     T\n1  0    │    T     F\n\n"
+        );
+    }
+
+    #[test]
+    fn test_to_filtered_string() {
+        let table = TruthTable::parse_expression_str("A | B");
+        assert_eq!(
+            table.to_filtered_string(false),
+            "A B │ Result\n────┼───────\n0 0 │      F\n\n"
+        );
+
+        let table = TruthTable::parse_expression_str("A & !A");
+        assert_eq!(
+            table.to_filtered_string(true),
+            "A │ Result\n──┼───────\nNo rows are true\n\n"
+        );
     }
 }
@@ -0,0 +1,557 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Output;
+use std::process::Stdio;
+
+// Returns an empty home directory, so that a config file in the real one cannot change the output
+fn empty_home() -> PathBuf {
+    let home = std::env::temp_dir().join(format!("truth_tables_empty_{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+
+    home
+}
+
+// Runs the truth_tables binary with the given arguments
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_truth_tables"))
+        .args(args)
+        .env("HOME", empty_home())
+        .output()
+        .expect("Failed to run the truth_tables binary")
+}
+
+// Runs the binary with the given arguments, writing the input to its stdin
+fn run_with_input(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_truth_tables"))
+        .args(args)
+        .env("HOME", empty_home())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to run the truth_tables binary");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+
+    child
+        .wait_with_output()
+        .expect("Failed to run the truth_tables binary")
+}
+
+#[test]
+fn test_check_valid_expression() {
+    let output = run(&["--check", "(A & B) | !C"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "OK\n");
+}
+
+#[test]
+fn test_check_invalid_expression() {
+    let output = run(&["--check", "A & (B ? C)"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Invalid character '?' at position 7 in expression\n    A & (B ? C)\n           ^\n"
+    );
+}
+
+#[test]
+fn test_check_multiple_errors() {
+    let output = run(&["--check", "A & B) | ?"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Unmatched ')' at position 5 in expression\n    A & B) | ?\n         ^\n\
+         Error: Invalid character '?' at position 9 in expression\n    A & B) | ?\n             ^\n"
+    );
+}
+
+#[test]
+fn test_expression_single_row() {
+    let output = run(&["-e", "A & B", "--row", "3"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B │ Result\n────┼───────\n1 1 │      T\n\n"
+    );
+
+    // Rows outside the table are reported instead of panicking
+    let output = run(&["-e", "A & B", "--row", "9"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Row 9 is out of range, the table only has rows 0 to 3\n"
+    );
+}
+
+#[test]
+fn test_truth_table_rows() {
+    let output = run(&["-t", "001, 011, 101, 110"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "(!A & !B) | (!A & B) | (A & !B)\n"
+    );
+}
+
+#[test]
+fn test_truth_table_invalid_rows() {
+    let output = run(&["-t", "001, 0111"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: All rows must be the same length\n"
+    );
+
+    let output = run(&["-t", "001, 011", "--vars", "3"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Expected 3 propositions, but the rows contain 2\n"
+    );
+}
+
+#[test]
+fn test_eval_assignment() {
+    let output = run(&["--eval", "A & (B | C)", "A=1,B=0,C=1"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "true\n");
+
+    let output = run(&["--eval", "A & (B | C)", "A=1,B=0"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Proposition 'C' is not assigned a value\n"
+    );
+}
+
+#[test]
+fn test_check_unclosed_parenthesis() {
+    let output = run(&["--check", "A | (A & B"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Unclosed '(' at position 4 in expression\n    A | (A & B\n        ^\n"
+    );
+}
+
+#[test]
+fn test_expression_allow_gaps() {
+    let output = run(&["-e", "A & C", "--allow-gaps"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A C │ Result\n────┼───────\n0 0 │      F\n0 1 │      F\n1 0 │      F\n1 1 │      T\n\n"
+    );
+}
+
+#[test]
+fn test_expression_names() {
+    let output = run(&["-e", "rain & !is_admin"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "is_admin rain │ Result\n\
+         ──────────────┼───────\n\
+         0        0    │      F\n\
+         0        1    │      T\n\
+         1        0    │      F\n\
+         1        1    │      F\n\n"
+    );
+
+    let output = run(&["--eval", "rain & !is_admin", "rain=1,is_admin=0"]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "true\n");
+}
+
+#[test]
+fn test_expression_verbose() {
+    let output = run(&["-e", "(A & B) | C", "--verbose", "--timing"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B C (A & B) │ Result\n\
+         ──────────────┼───────\n\
+         0 0 0 F       │      F\n\
+         0 0 1 F       │      T\n\
+         0 1 0 F       │      F\n\
+         0 1 1 F       │      T\n\
+         1 0 0 F       │      F\n\
+         1 0 1 F       │      T\n\
+         1 1 0 T       │      T\n\
+         1 1 1 T       │      T\n\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("Parsing: "));
+}
+
+#[test]
+fn test_diff() {
+    let output = run(&["--diff", "A -> B", "B -> A"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B │ Left Right\n\
+         ────┼───────────\n\
+         0 1 │    T     F\n\
+         1 0 │    F     T\n\n"
+    );
+
+    let output = run(&["--diff", "A -> B", "A & B & C"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Truth tables must contain the same propositions to be compared, but they contain (A, B) and (A, B, C)\n"
+    );
+}
+
+#[test]
+fn test_expression_only_true() {
+    let output = run(&["-e", "A & B", "--only-true"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B │ Result\n────┼───────\n1 1 │      T\n\n"
+    );
+}
+
+#[test]
+fn test_expression_timing() {
+    let output = run(&["--timing", "-e", "A & B"]);
+
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("A B │ Result\n"));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let steps: Vec<(&str, &str)> = stderr
+        .lines()
+        .map(|line| line.split_once(": ").unwrap())
+        .collect();
+    assert_eq!(
+        steps.iter().map(|(step, _)| *step).collect::<Vec<_>>(),
+        vec!["Parsing", "Tabulation", "Output"]
+    );
+
+    // Each duration is a number followed by a unit, such as "12.5µs"
+    for (_, duration) in steps {
+        let number = ["ns", "µs", "ms", "s"]
+            .iter()
+            .find_map(|unit| duration.strip_suffix(unit))
+            .unwrap();
+        assert!(number.parse::<f64>().is_ok());
+    }
+}
+
+#[test]
+fn test_repl() {
+    let output = run_with_input(&["--repl"], "A & B\n(A\nA | B\n");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B │ Result\n\
+         ────┼───────\n\
+         0 0 │      F\n\
+         0 1 │      F\n\
+         1 0 │      F\n\
+         1 1 │      T\n\n\
+         A B │ Result\n\
+         ────┼───────\n\
+         0 0 │      F\n\
+         0 1 │      T\n\
+         1 0 │      T\n\
+         1 1 │      T\n\n"
+    );
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Unclosed '(' at position 0 in expression\n"
+    );
+}
+
+#[test]
+fn test_expression_from_stdin() {
+    let output = run_with_input(&["-e", "-", "--only-true"], "# Both\nA &\n  B\n");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B │ Result\n\
+         ────┼───────\n\
+         1 1 │      T\n\n"
+    );
+}
+
+#[test]
+fn test_expression_from_file() {
+    let path = std::env::temp_dir().join(format!("truth_tables_{}.txt", std::process::id()));
+    std::fs::write(&path, "A |\n# Either\nB\n").unwrap();
+
+    let output = run(&["-f", path.to_str().unwrap(), "--only-false"]);
+    let text_output = run(&["-e", path.to_str().unwrap()]);
+    let missing_output = run(&["-f", "missing_truth_tables_file.txt"]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B │ Result\n\
+         ────┼───────\n\
+         0 0 │      F\n\n"
+    );
+
+    // An argument to -e is always expression text, even if a file exists at that path
+    assert!(!text_output.status.success());
+    assert!(text_output.stdout.is_empty());
+
+    assert!(!missing_output.status.success());
+    assert!(String::from_utf8_lossy(&missing_output.stderr)
+        .starts_with("Error: Could not read 'missing_truth_tables_file.txt': "));
+}
+
+#[test]
+fn test_minterms() {
+    let output = run(&["-m", "2", "Σm(0, 3)"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "(!A & !B) | (A & B)\n"
+    );
+
+    let output = run(&["--minterms", "2", "1,4"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Minterm 4 is out of range, the table only has rows 0 to 3\n"
+    );
+}
+
+#[test]
+fn test_max_depth_flag() {
+    let output = run(&["-e", "((A))", "--max-depth", "1"]);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Expression exceeds the maximum nesting depth\n"
+    );
+
+    let output = run(&["--max-depth", "2", "-e", "((A))"]);
+
+    assert!(output.status.success());
+
+    // Prefix and postfix notation use the same maximum depth
+    for (input, notation) in [("& & A B C", "--prefix"), ("A B & C &", "--postfix")] {
+        let output = run(&["--max-depth", "1", "-e", input, notation]);
+
+        assert!(!output.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&output.stderr),
+            "Error: Expression exceeds the maximum nesting depth\n"
+        );
+    }
+
+    // Huge depths are clamped instead of overflowing the stack
+    let nested = format!("{}A{}", "(".repeat(100_000), ")".repeat(100_000));
+    let output = run_with_input(&["--max-depth", "1000000", "-e", "-"], &nested);
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Expression exceeds the maximum nesting depth\n"
+    );
+}
+
+#[test]
+fn test_syntax_flag() {
+    let output = run(&["--syntax", "python", "-e", "not A or B", "--only-false"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B │ Result\n\
+         ────┼───────\n\
+         1 0 │      F\n\n"
+    );
+
+    let output = run(&["-t", "001, 011, 101, 110", "--syntax", "math"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "¬A ∧ ¬B ∨ ¬A ∧ B ∨ A ∧ ¬B\n"
+    );
+
+    // The disjunction can be pasted straight into a LaTeX document
+    let output = run(&["--syntax", "latex", "-t", "001, 011, 101, 110"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "\\neg A \\land \\neg B \\lor \\neg A \\land B \\lor A \\land \\neg B\n"
+    );
+}
+
+#[test]
+fn test_syntax_config_file() {
+    let home = std::env::temp_dir().join(format!("truth_tables_home_{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    std::fs::write(home.join(".truth_tables"), "# Defaults\nsyntax = c-style\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_truth_tables"))
+        .args(["--check", "A & B"])
+        .env("HOME", &home)
+        .output()
+        .expect("Failed to run the truth_tables binary");
+
+    // Comments can also follow a setting on the same line
+    std::fs::write(home.join(".truth_tables"), "syntax = math # note\n").unwrap();
+
+    let commented_output = Command::new(env!("CARGO_BIN_EXE_truth_tables"))
+        .args(["--check", "A ∧ B"])
+        .env("HOME", &home)
+        .output()
+        .expect("Failed to run the truth_tables binary");
+    std::fs::remove_dir_all(&home).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stderr),
+        "Error: Invalid character '&' at position 2 in expression\n    A & B\n      ^\n"
+    );
+
+    assert!(commented_output.status.success());
+    assert_eq!(String::from_utf8_lossy(&commented_output.stdout), "OK\n");
+}
+
+#[test]
+fn test_alias_config_file() {
+    let home = std::env::temp_dir().join(format!("truth_tables_aliases_{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+    std::fs::write(
+        home.join(".truth_tables"),
+        "alias = . & # dot\nalias = % ^\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_truth_tables"))
+        .args(["-e", "A . B % C", "--only-true"])
+        .env("HOME", &home)
+        .output()
+        .expect("Failed to run the truth_tables binary");
+    std::fs::remove_dir_all(&home).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B C │ Result\n\
+         ──────┼───────\n\
+         0 0 1 │      T\n\
+         0 1 1 │      T\n\
+         1 0 1 │      T\n\
+         1 1 0 │      T\n\n"
+    );
+}
+
+#[test]
+fn test_repl_comments() {
+    let output = run_with_input(&["--repl"], "# Homework\n\nA # only A\n");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A │ Result\n\
+         ──┼───────\n\
+         0 │      F\n\
+         1 │      T\n\n"
+    );
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn test_repl_modes() {
+    let output = run_with_input(&["--repl"], ":mode table\n001, 011, 101, 110\nquit\nA\n");
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "(!A & !B) | (!A & B) | (A & !B)\n"
+    );
+}
+
+#[test]
+fn test_expression_implicit_and() {
+    let output = run(&["-e", "AB + !A!B", "--implicit-and"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B │ Result\n\
+         ────┼───────\n\
+         0 0 │      T\n\
+         0 1 │      F\n\
+         1 0 │      F\n\
+         1 1 │      T\n\n"
+    );
+}
+
+#[test]
+fn test_expression_prefix() {
+    let output = run(&["-e", "| & A B ! A", "--prefix"]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "A B │ Result\n\
+         ────┼───────\n\
+         0 0 │      T\n\
+         0 1 │      T\n\
+         1 0 │      F\n\
+         1 1 │      T\n\n"
+    );
+}
+
+#[test]
+fn test_expression_postfix() {
+    let prefix = run(&["-e", "| & A B ! A", "--prefix"]);
+    let postfix = run(&["-e", "A B & A ! |", "--postfix"]);
+
+    assert!(postfix.status.success());
+    assert_eq!(postfix.stdout, prefix.stdout);
+
+    // Both notations use the chosen syntax profile
+    let prefix_python = run(&["--syntax", "python", "-e", "or and A B not A", "--prefix"]);
+    let postfix_python = run(&["--syntax", "python", "-e", "A B and A not or", "--postfix"]);
+
+    assert_eq!(prefix_python.stdout, prefix.stdout);
+    assert_eq!(postfix_python.stdout, prefix.stdout);
+
+    let output = run(&["--syntax", "python", "-e", "| A B", "--prefix"]);
+
+    assert!(!output.status.success());
+}
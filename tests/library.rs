@@ -0,0 +1,56 @@
+use std::error::Error;
+
+use truth_tables::Expression;
+use truth_tables::ParseError;
+use truth_tables::PropositionIdentifier;
+use truth_tables::RowParseError;
+use truth_tables::TruthTable;
+
+#[test]
+fn test_table_from_expression() {
+    let mut expression = Expression::parse("(A | B) & !C", true);
+    let table = TruthTable::from_expression(&mut expression);
+
+    assert_eq!(
+        table.propositions(),
+        ['A', 'B', 'C'].map(PropositionIdentifier::from_char)
+    );
+    assert_eq!(table, TruthTable::parse_expression_str("(A | B) & !C"));
+    assert_eq!(table.to_minterm_notation(), "f(A,B,C) = Σm(2,4,6)");
+
+    // The expression can still be used after building the table
+    assert!(expression.evaluate_permutation(table.satisfying_assignments()[0]));
+}
+
+#[test]
+fn test_expression_builders() {
+    let mut expression = Expression::or(
+        Expression::and(Expression::var("A"), !Expression::var("B")),
+        Expression::var("C"),
+    );
+
+    assert_eq!(expression.to_string(), "A & !B | C");
+    assert_eq!(
+        TruthTable::from_expression(&mut expression),
+        TruthTable::parse_expression_str("A & !B | C")
+    );
+}
+
+#[test]
+fn test_parse_traits() -> Result<(), Box<dyn Error>> {
+    let mut expression: Expression = "A & !B".parse()?;
+    let table = TruthTable::try_from("000, 010, 101, 110")?;
+
+    assert_eq!(TruthTable::from_expression(&mut expression), table);
+
+    assert_eq!(
+        "A & ?".parse::<Expression>(),
+        Err(ParseError::InvalidCharacter('?', 4))
+    );
+    assert_eq!(
+        TruthTable::try_from("00, 1"),
+        Err(RowParseError::InconsistentRowLength)
+    );
+
+    Ok(())
+}